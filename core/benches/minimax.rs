@@ -0,0 +1,66 @@
+//! Times a full Classic 4x6 minimax pass, scoring every code against every
+//! code, with unpacked and packed codes.
+//!
+//! Run with `cargo bench --bench minimax`.
+
+use std::{collections::HashMap, hint::black_box, time::Instant};
+
+use core::{
+    feedback::Feedback,
+    packed::PackedCode,
+    rules::{Limit, Rules},
+    solver::{self, MinimaxSolver, Solver},
+};
+
+const RUNS: u32 = 5;
+
+fn main() {
+    let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+    let codes = solver::all_codes(&rules);
+    let packed: Vec<PackedCode> = codes.iter().filter_map(|c| PackedCode::new(c)).collect();
+
+    let unpacked_pass = time("unpacked pass", || {
+        codes
+            .iter()
+            .map(|guess| {
+                let mut partition: HashMap<Feedback, usize> = HashMap::new();
+                for code in &codes {
+                    *partition.entry(Feedback::new(code, guess)).or_default() += 1;
+                }
+                partition.into_values().max().unwrap_or(0)
+            })
+            .min()
+    });
+    let packed_pass = time("packed pass", || {
+        packed
+            .iter()
+            .map(|guess| {
+                let mut partition = [0usize; 17 * 17];
+                for code in &packed {
+                    let (exact, matched) = code.peg_counts(guess);
+                    partition[exact as usize * 17 + matched as usize] += 1;
+                }
+                partition.into_iter().max().unwrap_or(0)
+            })
+            .min()
+    });
+    println!(
+        "packed codes are {:.1}x faster",
+        unpacked_pass / packed_pass.max(f64::MIN_POSITIVE)
+    );
+
+    time("MinimaxSolver first guess", || {
+        MinimaxSolver.next_guess(&rules, &[])
+    });
+}
+
+/// Runs `f` a few times and prints the mean time, in milliseconds.
+fn time<T>(name: &str, mut f: impl FnMut() -> T) -> f64 {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+    let millis = start.elapsed().as_secs_f64() * 1000.0 / RUNS as f64;
+    println!("{:<28} {:>9.2} ms", name, millis);
+    millis
+}
@@ -0,0 +1,572 @@
+//! Exhaustive search for optimal strategies.
+//!
+//! The search is a depth-first branch and bound over the secrets still
+//! possible. Guesses are tried in order of a lower bound on the guesses they
+//! lead to, and each set of secrets is solved at most once. Guesses related
+//! by a [`Symmetries`] of the first turns, or splitting the secrets the same
+//! way, are only tried once.
+//!
+//! With a checkpoint file, the result for each feedback to each opening guess
+//! is appended as soon as it is known, and read back by later runs:
+//!
+//! ```text
+//! rules 3 3 unlimited
+//! objective average
+//! class 0 0 1 = 0 0 depth unlimited exact 3
+//! 2 2 2
+//!   0 0 -> 2 2 2 *
+//! end
+//! class 0 0 0 = 0 0 depth 3 at-least 12
+//! ```
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    game::Turn,
+    replay::{self, Replay},
+    rules::Rules,
+    strategy::Strategy,
+    symmetry::Symmetries,
+    table::{FeedbackTable, TableError},
+};
+
+/// Largest feedback table the analysis builds.
+const MAX_TABLE_BYTES: usize = 1 << 26;
+/// Result of a search that found no strategy within the depth limit.
+const INFEASIBLE: usize = usize::MAX / 4;
+/// Depth limit of the average objective.
+const UNLIMITED: u8 = u8::MAX;
+/// Symmetries are looked for while the history is at most this long.
+const SYMMETRY_TURNS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Fewest guesses for the hardest secret, then fewest guesses in total.
+    WorstCase,
+    /// Fewest guesses in total, i.e. the best average.
+    Average,
+}
+
+#[derive(Debug)]
+pub enum AnalysisError {
+    Table(TableError),
+    Checkpoint(io::Error),
+    /// The checkpoint file is not one written by the analysis.
+    MalformedCheckpoint {
+        line: usize,
+    },
+    /// The checkpoint file was written for other rules or another objective.
+    CheckpointMismatch,
+}
+
+/// An optimal strategy and how it fares over every secret.
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    objective: Objective,
+    strategy: Strategy,
+    histogram: Vec<usize>,
+}
+
+impl Analysis {
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    pub fn strategy(&self) -> &Strategy {
+        &self.strategy
+    }
+
+    /// How many secrets are solved with each number of guesses, starting
+    /// with one guess.
+    pub fn histogram(&self) -> &[usize] {
+        &self.histogram
+    }
+
+    /// Most guesses needed for a secret.
+    pub fn worst_case(&self) -> usize {
+        self.histogram.len()
+    }
+
+    pub fn total_guesses(&self) -> usize {
+        self.strategy.total_guesses()
+    }
+
+    pub fn average(&self) -> f64 {
+        self.total_guesses() as f64 / self.histogram.iter().sum::<usize>() as f64
+    }
+}
+
+/// Finds a strategy that is optimal for the objective.
+///
+/// The search is exhaustive: Classic 4x6 takes minutes in a release build,
+/// and larger rules may not finish. With a checkpoint file, an interrupted
+/// analysis resumes where it stopped.
+pub fn analyse(
+    rules: &Rules,
+    objective: Objective,
+    checkpoint: Option<&Path>,
+) -> Result<Analysis, AnalysisError> {
+    let table = FeedbackTable::new(rules, MAX_TABLE_BYTES).map_err(AnalysisError::Table)?;
+    let checkpoint = match checkpoint {
+        Some(path) => Some(Checkpoint::open(path, rules, objective, &table)?),
+        None => None,
+    };
+    let mut search = Search::new(rules, table, checkpoint);
+    let secrets: Vec<u16> = (0..search.table.codes().len() as u16).collect();
+
+    let depth = match objective {
+        Objective::Average => {
+            search.solve(&secrets, &[], UNLIMITED, INFEASIBLE)?;
+            UNLIMITED
+        }
+        Objective::WorstCase => {
+            let mut depth = 1;
+            while search.capacity(depth) < secrets.len()
+                || search.solve(&secrets, &[], depth, INFEASIBLE)? >= INFEASIBLE
+            {
+                depth += 1;
+            }
+            depth
+        }
+    };
+    let strategy = search.strategy(&secrets, depth);
+    Ok(Analysis {
+        objective,
+        histogram: strategy.histogram(),
+        strategy,
+    })
+}
+
+/// What is known about the guesses needed for a set of secrets.
+#[derive(Debug, Clone, Copy)]
+enum Entry {
+    Exact { total: usize, guess: u16 },
+    AtLeast(usize),
+}
+
+/// A guess considered for a set of secrets, with the classes it splits them into.
+struct Split {
+    bound: usize,
+    guess: u16,
+    /// Feedback id and secrets of each class the guess does not solve.
+    classes: Vec<(u8, Vec<u16>)>,
+}
+
+struct Search {
+    table: FeedbackTable,
+    win: u8,
+    /// Most classes a guess can leave unsolved.
+    branching: usize,
+    memo: HashMap<(Vec<u16>, u8), Entry>,
+    /// Subtrees read from the checkpoint, by set of secrets and depth limit.
+    loaded: HashMap<(Vec<u16>, u8), Strategy>,
+    checkpoint: Option<Checkpoint>,
+}
+
+impl Search {
+    fn new(rules: &Rules, table: FeedbackTable, checkpoint: Option<Checkpoint>) -> Self {
+        let code = &table.codes()[0];
+        let win = table
+            .id_of(&rules.scheme().score(code, code))
+            .expect("a win is a possible feedback");
+        Search {
+            branching: table.feedbacks().len() - 1,
+            win,
+            table,
+            memo: HashMap::new(),
+            loaded: HashMap::new(),
+            checkpoint,
+        }
+    }
+
+    /// Fewest guesses needed for `secrets` within `depth` guesses, if below
+    /// `alpha`; otherwise some lower bound of at least `alpha`.
+    fn solve(
+        &mut self,
+        secrets: &[u16],
+        history: &[Turn],
+        depth: u8,
+        alpha: usize,
+    ) -> Result<usize, AnalysisError> {
+        let count = secrets.len();
+        if count > self.capacity(depth) {
+            return Ok(INFEASIBLE);
+        }
+        if count == 1 {
+            return Ok(1);
+        }
+        let key = (secrets.to_vec(), depth);
+        match self.memo.get(&key) {
+            Some(&Entry::Exact { total, .. }) => return Ok(total),
+            Some(&Entry::AtLeast(bound)) if bound >= alpha => return Ok(bound),
+            _ => {}
+        }
+        if history.len() == 1
+            && !self.memo.contains_key(&key)
+            && let Some(entry) = self.load(&key, &history[0])
+        {
+            self.memo.insert(key.clone(), entry);
+            match entry {
+                Entry::Exact { total, .. } => return Ok(total),
+                Entry::AtLeast(bound) if bound >= alpha => return Ok(bound),
+                Entry::AtLeast(_) => {}
+            }
+        }
+
+        let child_depth = if depth == UNLIMITED { depth } else { depth - 1 };
+        let mut best = alpha;
+        let mut best_guess = None;
+        for option in self.options(secrets, history, child_depth) {
+            if option.bound >= best {
+                break;
+            }
+            let mut total = count;
+            let mut rest = option.bound - count;
+            let mut complete = true;
+            for (id, class) in &option.classes {
+                let lower = self.lower_bound(class.len());
+                rest -= lower;
+                let mut child_history = history.to_vec();
+                child_history.push(self.turn(option.guess, *id));
+                total += self.solve(class, &child_history, child_depth, best - total - rest)?;
+                if total + rest >= best {
+                    complete = false;
+                    break;
+                }
+            }
+            if complete {
+                best = total;
+                best_guess = Some(option.guess);
+            }
+        }
+
+        let entry = match best_guess {
+            Some(guess) => Entry::Exact { total: best, guess },
+            None => Entry::AtLeast(alpha),
+        };
+        self.memo.insert(key.clone(), entry);
+        if history.len() == 1 {
+            self.save(&key, &history[0], entry)?;
+        }
+        Ok(best)
+    }
+
+    /// The guesses worth trying for `secrets`, best bound first.
+    fn options(&self, secrets: &[u16], history: &[Turn], child_depth: u8) -> Vec<Split> {
+        let symmetries =
+            (history.len() <= SYMMETRY_TURNS).then(|| Symmetries::new(self.table.rules(), history));
+        let mut seen: HashSet<Vec<u8>> = HashSet::new();
+        let mut options = Vec::new();
+        for guess in 0..self.table.codes().len() as u16 {
+            if let Some(symmetries) = &symmetries
+                && !symmetries.is_representative(&self.table.codes()[guess as usize])
+            {
+                continue;
+            }
+            let row = self.table.row(guess as usize);
+            let ids: Vec<u8> = secrets.iter().map(|&s| row[s as usize]).collect();
+            let mut classes: Vec<Vec<u16>> = vec![Vec::new(); self.table.feedbacks().len()];
+            for (&secret, &id) in secrets.iter().zip(&ids) {
+                classes[id as usize].push(secret);
+            }
+            let useless = classes.iter().any(|class| class.len() == secrets.len())
+                && classes[self.win as usize].is_empty();
+            let too_large = classes.iter().enumerate().any(|(id, class)| {
+                id != self.win as usize && class.len() > self.capacity(child_depth)
+            });
+            if useless || too_large || !seen.insert(ids) {
+                continue;
+            }
+            let mut classes: Vec<(u8, Vec<u16>)> = classes
+                .into_iter()
+                .enumerate()
+                .filter(|(id, class)| *id != self.win as usize && !class.is_empty())
+                .map(|(id, class)| (id as u8, class))
+                .collect();
+            // Large classes first, as they decide most of the cost
+            classes.sort_by_key(|(id, class)| (std::cmp::Reverse(class.len()), *id));
+            let bound = secrets.len()
+                + classes
+                    .iter()
+                    .map(|(_, class)| self.lower_bound(class.len()))
+                    .sum::<usize>();
+            options.push(Split {
+                bound,
+                guess,
+                classes,
+            });
+        }
+        options.sort_by_key(|option| (option.bound, option.guess));
+        options
+    }
+
+    /// Fewest guesses in total for `secrets` secrets, if every guess could be
+    /// the secret and split the others as evenly as the feedback allows.
+    fn lower_bound(&self, secrets: usize) -> usize {
+        let mut left = secrets;
+        let mut level = 1usize;
+        let mut guesses = 1;
+        let mut total = 0;
+        while left > 0 {
+            let solved = left.min(level);
+            total += solved * guesses;
+            left -= solved;
+            level = level.saturating_mul(self.branching);
+            guesses += 1;
+        }
+        total
+    }
+
+    /// Most secrets that can be solved within `depth` guesses.
+    fn capacity(&self, depth: u8) -> usize {
+        if depth == UNLIMITED {
+            return usize::MAX;
+        }
+        let mut level = 1usize;
+        let mut capacity = 0usize;
+        for _ in 0..depth {
+            capacity = capacity.saturating_add(level);
+            level = level.saturating_mul(self.branching);
+        }
+        capacity
+    }
+
+    fn turn(&self, guess: u16, id: u8) -> Turn {
+        Turn::new(
+            self.table.codes()[guess as usize].clone(),
+            self.table.feedbacks()[id as usize].clone(),
+        )
+    }
+
+    /// Rebuilds the strategy found for `secrets`.
+    fn strategy(&self, secrets: &[u16], depth: u8) -> Strategy {
+        let codes = self.table.codes();
+        if let [secret] = secrets {
+            return Strategy::new(codes[*secret as usize].clone(), true, Vec::new());
+        }
+        let key = (secrets.to_vec(), depth);
+        if let Some(strategy) = self.loaded.get(&key) {
+            return strategy.clone();
+        }
+        let Some(&Entry::Exact { guess, .. }) = self.memo.get(&key) else {
+            unreachable!("every class of a complete strategy is solved");
+        };
+        let child_depth = if depth == UNLIMITED { depth } else { depth - 1 };
+        let row = self.table.row(guess as usize);
+        let mut branches = Vec::new();
+        for id in 0..self.table.feedbacks().len() as u8 {
+            let class: Vec<u16> = secrets
+                .iter()
+                .copied()
+                .filter(|&s| row[s as usize] == id)
+                .collect();
+            if id != self.win && !class.is_empty() {
+                let feedback = self.table.feedbacks()[id as usize].clone();
+                branches.push((feedback, self.strategy(&class, child_depth)));
+            }
+        }
+        let solves = secrets.contains(&guess);
+        Strategy::new(codes[guess as usize].clone(), solves, branches)
+    }
+
+    /// Looks up the checkpoint for a class of an opening guess.
+    fn load(&mut self, key: &(Vec<u16>, u8), turn: &Turn) -> Option<Entry> {
+        let record = self
+            .checkpoint
+            .as_ref()?
+            .records
+            .get(&record_key(turn, key.1))?
+            .clone();
+        match record {
+            Record::Exact { total, strategy } => {
+                let guess = self.table.index_of(strategy.guess())? as u16;
+                self.loaded.insert(key.clone(), strategy);
+                Some(Entry::Exact { total, guess })
+            }
+            Record::AtLeast(bound) => Some(Entry::AtLeast(bound)),
+        }
+    }
+
+    /// Appends the result for a class of an opening guess to the checkpoint.
+    fn save(
+        &mut self,
+        key: &(Vec<u16>, u8),
+        turn: &Turn,
+        entry: Entry,
+    ) -> Result<(), AnalysisError> {
+        if self.checkpoint.is_none() {
+            return Ok(());
+        }
+        let record = match entry {
+            Entry::Exact { total, .. } => Record::Exact {
+                total,
+                strategy: self.strategy(&key.0, key.1),
+            },
+            Entry::AtLeast(bound) => Record::AtLeast(bound),
+        };
+        let checkpoint = self.checkpoint.as_mut().expect("checked above");
+        checkpoint
+            .append(turn, key.1, &record)
+            .map_err(AnalysisError::Checkpoint)?;
+        checkpoint.records.insert(record_key(turn, key.1), record);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Record {
+    Exact { total: usize, strategy: Strategy },
+    AtLeast(usize),
+}
+
+/// Results for the classes of opening guesses, kept in a file.
+struct Checkpoint {
+    records: HashMap<String, Record>,
+    file: BufWriter<File>,
+}
+
+impl Checkpoint {
+    /// Reads the records of an existing checkpoint file, or starts a new one.
+    fn open(
+        path: &Path,
+        rules: &Rules,
+        objective: Objective,
+        table: &FeedbackTable,
+    ) -> Result<Self, AnalysisError> {
+        let header = format!(
+            "{}objective {}\n",
+            Replay::new(rules.clone(), Vec::new(), None),
+            objective_name(objective)
+        );
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(AnalysisError::Checkpoint(error)),
+        };
+        // A header cut short is written again
+        let (records, valid) = if header.starts_with(text.as_str()) {
+            (HashMap::new(), 0)
+        } else if let Some(body) = text.strip_prefix(&header) {
+            let (records, length) = parse_records(body, rules, table, header.lines().count())?;
+            (records, header.len() + length)
+        } else {
+            return Err(AnalysisError::CheckpointMismatch);
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(AnalysisError::Checkpoint)?;
+        // New records go right after the last complete one
+        file.set_len(valid as u64)
+            .map_err(AnalysisError::Checkpoint)?;
+        let mut file = BufWriter::new(file);
+        if valid == 0 {
+            file.write_all(header.as_bytes())
+                .and_then(|_| file.flush())
+                .map_err(AnalysisError::Checkpoint)?;
+        }
+        Ok(Checkpoint { records, file })
+    }
+
+    fn append(&mut self, turn: &Turn, depth: u8, record: &Record) -> io::Result<()> {
+        write!(self.file, "{}", record_key(turn, depth))?;
+        match record {
+            Record::Exact { total, strategy } => {
+                writeln!(self.file, " exact {}", total)?;
+                write!(self.file, "{}", strategy)?;
+                writeln!(self.file, "end")?;
+            }
+            Record::AtLeast(bound) => writeln!(self.file, " at-least {}", bound)?,
+        }
+        self.file.flush()
+    }
+}
+
+fn objective_name(objective: Objective) -> &'static str {
+    match objective {
+        Objective::WorstCase => "worst-case",
+        Objective::Average => "average",
+    }
+}
+
+/// The start of a record line, which identifies the class.
+fn record_key(turn: &Turn, depth: u8) -> String {
+    let depth = match depth {
+        UNLIMITED => "unlimited".to_string(),
+        depth => depth.to_string(),
+    };
+    format!(
+        "class {} = {} depth {}",
+        replay::format_code(turn.guess()),
+        replay::format_feedback(turn.feedback()),
+        depth
+    )
+}
+
+/// Reads the records of a checkpoint file after its header.
+///
+/// # Returns
+///
+/// The records, and the length of the body up to the end of the last
+/// complete one. A record cut short by an interruption, and anything after
+/// it, is left out.
+fn parse_records(
+    body: &str,
+    rules: &Rules,
+    table: &FeedbackTable,
+    first_line: usize,
+) -> Result<(HashMap<String, Record>, usize), AnalysisError> {
+    let mut records = HashMap::new();
+    let mut valid = 0;
+    // A line without its newline was not written completely
+    let mut lines = body
+        .split_inclusive('\n')
+        .filter_map(|line| line.strip_suffix('\n'))
+        .enumerate()
+        .peekable();
+    while let Some((index, line)) = lines.next() {
+        let line_number = first_line + index + 1;
+        let malformed = || AnalysisError::MalformedCheckpoint { line: line_number };
+        let mut length = line.len() + 1;
+        let (key, result) = match (line.rsplit_once(" exact "), line.rsplit_once(" at-least ")) {
+            (Some((key, total)), _) => {
+                let total = total.parse().map_err(|_| malformed())?;
+                let mut tree = String::new();
+                let mut ended = false;
+                while let Some((_, line)) = lines.next_if(|(_, l)| !l.starts_with("class ")) {
+                    length += line.len() + 1;
+                    if line == "end" {
+                        ended = true;
+                        break;
+                    }
+                    tree.push_str(line);
+                    tree.push('\n');
+                }
+                if !ended {
+                    break;
+                }
+                let strategy = Strategy::parse(&tree, rules).map_err(|_| malformed())?;
+                table.index_of(strategy.guess()).ok_or_else(malformed)?;
+                (key, Record::Exact { total, strategy })
+            }
+            (None, Some((key, bound))) => {
+                let bound = bound.parse().map_err(|_| malformed())?;
+                (key, Record::AtLeast(bound))
+            }
+            (None, None) => return Err(malformed()),
+        };
+        if !key.starts_with("class ") {
+            return Err(malformed());
+        }
+        records.insert(key.to_string(), result);
+        valid += length;
+    }
+    Ok((records, valid))
+}
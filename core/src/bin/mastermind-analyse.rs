@@ -0,0 +1,61 @@
+//! Computes an optimal strategy for some rules and prints how many secrets it
+//! solves with each number of guesses.
+//!
+//! Usage: `mastermind-analyse CODE_LEN SYMBOLS [average|worst-case] [CHECKPOINT]`
+//!
+//! With a checkpoint file, an interrupted analysis resumes where it stopped.
+
+use std::{env, path::Path, process::ExitCode};
+
+use core::{
+    analysis::{self, Objective},
+    rules::{Limit, Rules},
+};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (code_len, symbols) = match (args.first(), args.get(1)) {
+        (Some(code_len), Some(symbols)) => (code_len.parse(), symbols.parse()),
+        _ => return usage(),
+    };
+    let (Ok(code_len), Ok(symbols)) = (code_len, symbols) else {
+        return usage();
+    };
+    let objective = match args.get(2).map(String::as_str) {
+        None | Some("average") => Objective::Average,
+        Some("worst-case") => Objective::WorstCase,
+        Some(_) => return usage(),
+    };
+    let checkpoint = args.get(3).map(Path::new);
+
+    let rules = match Rules::new(code_len, Limit::NoLimitation, symbols) {
+        Ok(rules) => rules,
+        Err(err) => {
+            eprintln!("error: {:?}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let analysis = match analysis::analyse(&rules, objective, checkpoint) {
+        Ok(analysis) => analysis,
+        Err(err) => {
+            eprintln!("error: {:?}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for (index, count) in analysis.histogram().iter().enumerate() {
+        println!("{:>3} guesses: {}", index + 1, count);
+    }
+    println!(
+        "total {} guesses, average {:.4}, worst case {}",
+        analysis.total_guesses(),
+        analysis.average(),
+        analysis.worst_case()
+    );
+    ExitCode::SUCCESS
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: mastermind-analyse CODE_LEN SYMBOLS [average|worst-case] [CHECKPOINT]");
+    ExitCode::FAILURE
+}
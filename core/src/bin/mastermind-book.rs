@@ -0,0 +1,73 @@
+//! Adds the minimax opening for some rules to an opening book file.
+//!
+//! Usage: `mastermind-book FILE CODE_LEN SYMBOLS [classic|exact|wordle|bulls-and-cows]`
+//!
+//! `mastermind-tree` plays the minimax solver from the book when the
+//! `MASTERMIND_BOOK` environment variable names the file.
+
+use std::{
+    env,
+    fs::File,
+    io::{BufWriter, ErrorKind},
+    path::Path,
+    process::ExitCode,
+};
+
+use core::{
+    book::{BookError, OpeningBook},
+    rules::{Limit, Rules},
+    scheme::Scheme,
+    solver::MinimaxSolver,
+};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (Some(path), Some(code_len), Some(symbols)) = (args.first(), args.get(1), args.get(2))
+    else {
+        return usage();
+    };
+    let (Ok(code_len), Ok(symbols)) = (code_len.parse(), symbols.parse()) else {
+        return usage();
+    };
+    let scheme = match args.get(3).map(String::as_str) {
+        None | Some("classic") => Scheme::Classic,
+        Some("exact") => Scheme::ExactOnly,
+        Some("wordle") => Scheme::Wordle,
+        Some("bulls-and-cows") => Scheme::BullsAndCows,
+        Some(_) => return usage(),
+    };
+    let rules = match Rules::new(code_len, Limit::NoLimitation, symbols)
+        .and_then(|rules| rules.with_scheme(scheme))
+    {
+        Ok(rules) => rules,
+        Err(err) => {
+            eprintln!("error: {:?}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut book = match OpeningBook::open(Path::new(path)) {
+        Ok(book) => book,
+        Err(BookError::Io(err)) if err.kind() == ErrorKind::NotFound => OpeningBook::new(),
+        Err(err) => {
+            eprintln!("error: {:?}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if !book.generate(&mut MinimaxSolver, &rules) {
+        eprintln!("error: the solver does not find every secret");
+        return ExitCode::FAILURE;
+    }
+    let saved = File::create(path).and_then(|file| book.save(BufWriter::new(file)));
+    if let Err(err) = saved {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+    eprintln!("{} opening(s) in {}", book.len(), path);
+    ExitCode::SUCCESS
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: mastermind-book FILE CODE_LEN SYMBOLS [classic|exact|wordle|bulls-and-cows]");
+    ExitCode::FAILURE
+}
@@ -0,0 +1,50 @@
+//! Compares each turn of a game given in the replay text format with the
+//! minimax solver's move, and points out blunders.
+//!
+//! Usage: `mastermind-report FILE [text|json]`
+
+use std::{env, fs, process::ExitCode};
+
+use core::{replay::Replay, report::GameReport};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(path) = args.first() else {
+        return usage();
+    };
+    let json = match args.get(1).map(String::as_str) {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(_) => return usage(),
+    };
+    let input = match fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let replay: Replay = match input.parse() {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("error: {:?}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(report) = GameReport::from_replay(&replay) else {
+        eprintln!("error: the turns contradict each other");
+        return ExitCode::FAILURE;
+    };
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        print!("{}", report);
+    }
+    ExitCode::SUCCESS
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: mastermind-report FILE [text|json]");
+    ExitCode::FAILURE
+}
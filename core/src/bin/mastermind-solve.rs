@@ -0,0 +1,62 @@
+//! Lists every code consistent with a board given in the replay text format.
+//!
+//! Usage: `mastermind-solve [FILE]` (reads standard input without a file).
+
+use std::{env, fs, io::Read, process::ExitCode};
+
+use core::{
+    puzzle::{self, PuzzleError},
+    replay::{self, Replay},
+};
+
+fn main() -> ExitCode {
+    let input = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(&path),
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).map(|_| input)
+        }
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let replay: Replay = match input.parse() {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("error: {:?}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match puzzle::solve(replay.rules(), replay.turns()) {
+        Ok(solutions) => {
+            for code in &solutions {
+                println!("{}", replay::format_code(code));
+            }
+            eprintln!("{} consistent code(s)", solutions.len());
+            ExitCode::SUCCESS
+        }
+        Err(PuzzleError::WrongCodeLength { turn }) => {
+            eprintln!("error: turn {} has the wrong code length", turn + 1);
+            ExitCode::FAILURE
+        }
+        Err(PuzzleError::Contradiction { conflicting }) => {
+            println!("contradictory turns:");
+            for turn in conflicting {
+                let guess = replay.turns()[turn].guess();
+                let feedback = replay.turns()[turn].feedback();
+                println!(
+                    "  turn {}: {} = {}",
+                    turn + 1,
+                    replay::format_code(guess),
+                    replay::format_feedback(feedback)
+                );
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Prints the decision tree a solver follows for every secret.
+//!
+//! Usage: `mastermind-tree CODE_LEN SYMBOLS [minimax|consistent] [text|json|dot]`
+//!
+//! The text format can be read back with `Strategy::parse` and played as an
+//! opening book. The minimax solver plays the opening for the rules from the
+//! book file named by the `MASTERMIND_BOOK` environment variable, if it is
+//! set.
+
+use std::{env, path::Path, process::ExitCode};
+
+use core::{
+    book::{BOOK_VAR, OpeningBook},
+    rules::{Limit, Rules},
+    solver::{ConsistentSolver, MinimaxSolver, Solver},
+    strategy::{BookSolver, Strategy},
+};
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (code_len, symbols) = match (args.first(), args.get(1)) {
+        (Some(code_len), Some(symbols)) => (code_len.parse(), symbols.parse()),
+        _ => return usage(),
+    };
+    let (Ok(code_len), Ok(symbols)) = (code_len, symbols) else {
+        return usage();
+    };
+    let minimax = match args.get(2).map(String::as_str) {
+        None | Some("minimax") => true,
+        Some("consistent") => false,
+        Some(_) => return usage(),
+    };
+
+    let rules = match Rules::new(code_len, Limit::NoLimitation, symbols) {
+        Ok(rules) => rules,
+        Err(err) => {
+            eprintln!("error: {:?}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let book = match env::var_os(BOOK_VAR) {
+        Some(path) => match OpeningBook::open(Path::new(&path)) {
+            Ok(book) => book,
+            Err(err) => {
+                eprintln!("error: {}: {:?}", path.to_string_lossy(), err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => OpeningBook::new(),
+    };
+    let mut solver: Box<dyn Solver> = match (minimax, book.opening(&rules)) {
+        (true, Some(opening)) => Box::new(BookSolver::new(opening.clone(), MinimaxSolver)),
+        (true, None) => Box::new(MinimaxSolver),
+        (false, _) => Box::new(ConsistentSolver),
+    };
+    let Some(strategy) = Strategy::from_solver(solver.as_mut(), &rules) else {
+        eprintln!("error: the solver does not find every secret");
+        return ExitCode::FAILURE;
+    };
+
+    match args.get(3).map(String::as_str) {
+        None | Some("text") => print!("{}", strategy),
+        Some("json") => println!("{}", strategy.to_json()),
+        Some("dot") => print!("{}", strategy.to_dot()),
+        Some(_) => return usage(),
+    }
+    eprintln!(
+        "{} secrets, {} guesses in total, worst case {}",
+        strategy.secrets(),
+        strategy.total_guesses(),
+        strategy.worst_case()
+    );
+    ExitCode::SUCCESS
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: mastermind-tree CODE_LEN SYMBOLS [minimax|consistent] [text|json|dot]");
+    ExitCode::FAILURE
+}
@@ -0,0 +1,212 @@
+//! Opening books: the first guess and the reply to each of its feedbacks,
+//! computed offline so that solvers do not search for them on every game.
+//!
+//! Saved books list one opening per rules, in the tree format of
+//! [`Strategy`]:
+//!
+//! ```text
+//! mastermind-book 1
+//! opening 3 3 classic
+//! 0 0 1 *
+//!   0 0 -> 2 2 2 *
+//!   1 0 -> 0 2 2 *
+//! end
+//! ```
+//!
+//! A solver only plays from a book it is given, through a [`BookSolver`]
+//! following the opening for the rules being played.
+//!
+//! [`BookSolver`]: crate::strategy::BookSolver
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+};
+
+use crate::{
+    game::{Code, Turn},
+    replay,
+    rules::{CodeLen, Limit, Rules},
+    scheme::Scheme,
+    solver::Solver,
+    strategy::{Strategy, StrategyError},
+};
+
+const HEADER: &str = "mastermind-book 1";
+/// Environment variable naming the book file the binaries play from.
+pub const BOOK_VAR: &str = "MASTERMIND_BOOK";
+
+#[derive(Debug)]
+pub enum BookError {
+    Malformed {
+        line: usize,
+    },
+    /// The opening starting at `line` is not a valid tree for its rules.
+    InvalidOpening {
+        line: usize,
+        error: StrategyError,
+    },
+    Io(io::Error),
+}
+
+impl From<io::Error> for BookError {
+    fn from(error: io::Error) -> Self {
+        BookError::Io(error)
+    }
+}
+
+/// The rules an opening is for. The time limit or attempt limit does not
+/// change which guesses are best, so it is left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key {
+    code_len: CodeLen,
+    symbols: u8,
+    scheme: Scheme,
+}
+
+impl Key {
+    fn of(rules: &Rules) -> Self {
+        Key {
+            code_len: rules.code_len(),
+            symbols: rules.available_symbols(),
+            scheme: rules.scheme(),
+        }
+    }
+}
+
+/// Openings for any number of rules.
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    openings: HashMap<Key, Strategy>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the solver's first guess and its reply to each feedback.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the solver gives up before finding every secret.
+    pub fn generate<S: Solver + ?Sized>(&mut self, solver: &mut S, rules: &Rules) -> bool {
+        match Strategy::opening(solver, rules, 2) {
+            Some(opening) => {
+                self.insert(rules, opening);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Keeps the first two guesses of the strategy as the opening for the
+    /// rules, replacing any previous one.
+    pub fn insert(&mut self, rules: &Rules, strategy: Strategy) {
+        self.openings.insert(Key::of(rules), strategy.truncated(2));
+    }
+
+    pub fn opening(&self, rules: &Rules) -> Option<&Strategy> {
+        self.openings.get(&Key::of(rules))
+    }
+
+    /// Number of rules the book has an opening for.
+    pub fn len(&self) -> usize {
+        self.openings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.openings.is_empty()
+    }
+
+    /// The book's guess after `history`, if the history is still in the
+    /// opening.
+    pub fn lookup(&self, rules: &Rules, history: &[Turn]) -> Option<Code> {
+        let node = self.opening(rules)?.follow(history)?;
+        Some(node.guess().clone())
+    }
+
+    /// Adds the openings of `other`, which replace those for the same rules.
+    pub fn merge(&mut self, other: OpeningBook) {
+        self.openings.extend(other.openings);
+    }
+
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+        let mut keys: Vec<&Key> = self.openings.keys().collect();
+        keys.sort_by_key(|key| (key.code_len, key.symbols, replay::scheme_name(key.scheme)));
+        for key in keys {
+            writeln!(
+                writer,
+                "opening {} {} {}",
+                key.code_len,
+                key.symbols,
+                replay::scheme_name(key.scheme)
+            )?;
+            write!(writer, "{}", self.openings[key])?;
+            writeln!(writer, "end")?;
+        }
+        writer.flush()
+    }
+
+    /// Reads the book saved in the file at `path`.
+    pub fn open(path: &Path) -> Result<Self, BookError> {
+        Self::load(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a book written by [`OpeningBook::save`].
+    pub fn load<R: BufRead>(reader: R) -> Result<Self, BookError> {
+        let mut lines = reader.lines().enumerate();
+        let header = lines.next().map(|(_, line)| line).transpose()?;
+        if header.as_deref() != Some(HEADER) {
+            return Err(BookError::Malformed { line: 1 });
+        }
+
+        let mut book = OpeningBook::new();
+        while let Some((index, line)) = lines.next() {
+            let line = line?;
+            let start = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let rules = match words[..] {
+                ["opening", code_len, symbols, scheme] => rules_of(code_len, symbols, scheme)
+                    .ok_or(BookError::Malformed { line: start })?,
+                _ => return Err(BookError::Malformed { line: start }),
+            };
+
+            let mut tree = String::new();
+            let mut ended = false;
+            for (_, line) in lines.by_ref() {
+                let line = line?;
+                if line == "end" {
+                    ended = true;
+                    break;
+                }
+                tree.push_str(&line);
+                tree.push('\n');
+            }
+            if !ended {
+                return Err(BookError::Malformed { line: start });
+            }
+            let opening = Strategy::parse(&tree, &rules)
+                .map_err(|error| BookError::InvalidOpening { line: start, error })?;
+            book.insert(&rules, opening);
+        }
+        Ok(book)
+    }
+}
+
+fn rules_of(code_len: &str, symbols: &str, scheme: &str) -> Option<Rules> {
+    Rules::new(
+        code_len.parse().ok()?,
+        Limit::NoLimitation,
+        symbols.parse().ok()?,
+    )
+    .ok()?
+    .with_scheme(replay::parse_scheme(scheme)?)
+    .ok()
+}
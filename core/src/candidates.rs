@@ -0,0 +1,206 @@
+//! Sets of codes stored as one bit per code of the rules, indexed by the
+//! code's rank in [`CodeSpace`].
+//!
+//! A set of all 4x6 codes takes 162 bytes instead of over 30 KB of vectors,
+//! and copies, unions and intersections work a word at a time.
+
+use crate::{
+    enumeration::CodeSpace,
+    game::{Code, Turn},
+    packed::PackedCode,
+    rules::Rules,
+    scheme::Scheme,
+};
+
+/// A set of codes allowed by some rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateSet {
+    space: CodeSpace,
+    scheme: Scheme,
+    words: Vec<u64>,
+}
+
+impl CandidateSet {
+    /// The set of every code allowed by the rules.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the codes cannot be numbered, or there are too many to give
+    /// each a bit in memory.
+    pub fn full(rules: &Rules) -> Option<Self> {
+        let mut set = Self::empty(rules)?;
+        set.words.iter_mut().for_each(|word| *word = u64::MAX);
+        let extra = set.words.len() as u64 * 64 - set.space.size();
+        if let Some(last) = set.words.last_mut() {
+            *last >>= extra;
+        }
+        Some(set)
+    }
+
+    /// The empty set of codes for the rules.
+    pub fn empty(rules: &Rules) -> Option<Self> {
+        let space = CodeSpace::new(rules)?;
+        let words = usize::try_from(space.size().div_ceil(64)).ok()?;
+        Some(CandidateSet {
+            space,
+            scheme: rules.scheme(),
+            words: vec![0; words],
+        })
+    }
+
+    /// The codes consistent with every turn of the history.
+    pub fn from_history(rules: &Rules, history: &[Turn]) -> Option<Self> {
+        let mut set = Self::full(rules)?;
+        for turn in history {
+            set.filter(turn);
+        }
+        Some(set)
+    }
+
+    /// Number of codes in the set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn contains(&self, code: &[u8]) -> bool {
+        self.space
+            .rank(code)
+            .is_some_and(|rank| self.words[(rank / 64) as usize] & (1 << (rank % 64)) != 0)
+    }
+
+    /// Adds a code, returning `false` if the rules do not allow it.
+    pub fn insert(&mut self, code: &[u8]) -> bool {
+        match self.space.rank(code) {
+            Some(rank) => {
+                self.words[(rank / 64) as usize] |= 1 << (rank % 64);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, code: &[u8]) {
+        if let Some(rank) = self.space.rank(code) {
+            self.words[(rank / 64) as usize] &= !(1 << (rank % 64));
+        }
+    }
+
+    /// Keeps only the codes that, as the secret, would give the turn its
+    /// feedback.
+    pub fn filter(&mut self, turn: &Turn) {
+        let scheme = self.scheme;
+        let feedback = turn.feedback();
+        // Pegs only need the exact and matched counts of packed codes
+        let packed_guess = PackedCode::new(turn.guess()).filter(|_| scheme != Scheme::Wordle);
+        match packed_guess {
+            Some(guess) => {
+                let exact = feedback.exact();
+                let matched = feedback.misplaced().map(|misplaced| exact + misplaced);
+                self.retain(|code| match PackedCode::new(code) {
+                    Some(code) => {
+                        let (e, m) = code.peg_counts(&guess);
+                        e == exact && matched.is_none_or(|matched| m == matched)
+                    }
+                    None => scheme.score(code, turn.guess()) == *feedback,
+                });
+            }
+            None => self.retain(|code| scheme.score(code, turn.guess()) == *feedback),
+        }
+    }
+
+    /// The codes of the set that would give the turn its feedback, leaving
+    /// the set as it is.
+    pub fn filtered(&self, turn: &Turn) -> Self {
+        let mut set = self.clone();
+        set.filter(turn);
+        set
+    }
+
+    /// Keeps only the codes for which `keep` returns `true`.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Code) -> bool) {
+        let mut kept = vec![0u64; self.words.len()];
+        for (rank, code) in self.ranked() {
+            if keep(&code) {
+                kept[(rank / 64) as usize] |= 1 << (rank % 64);
+            }
+        }
+        self.words = kept;
+    }
+
+    /// The codes in either set.
+    ///
+    /// # Panics
+    ///
+    /// If the sets are for different rules.
+    pub fn union(&self, other: &CandidateSet) -> Self {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// The codes in both sets.
+    ///
+    /// # Panics
+    ///
+    /// If the sets are for different rules.
+    pub fn intersection(&self, other: &CandidateSet) -> Self {
+        self.combine(other, |a, b| a & b)
+    }
+
+    /// The codes of the set in lexicographic order.
+    pub fn iter(&self) -> impl Iterator<Item = Code> + '_ {
+        self.ranked().map(|(_, code)| code)
+    }
+
+    /// The codes of the set with their ranks. Dense sets are walked code by
+    /// code; sparse ones unrank each code.
+    fn ranked(&self) -> Box<dyn Iterator<Item = (u64, Code)> + '_> {
+        let ranks = self.words.iter().enumerate().flat_map(|(index, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as u64;
+                word &= word - 1;
+                Some(index as u64 * 64 + bit)
+            })
+        });
+        // Unranking costs about one pass over the symbols per position
+        if (self.len() as u64).saturating_mul(8) < self.space.size() {
+            Box::new(ranks.filter_map(|rank| Some((rank, self.space.unrank(rank)?))))
+        } else {
+            Box::new(
+                self.space
+                    .iter()
+                    .enumerate()
+                    .map(|(rank, code)| (rank as u64, code))
+                    .filter(|&(rank, _)| {
+                        self.words[(rank / 64) as usize] & (1 << (rank % 64)) != 0
+                    }),
+            )
+        }
+    }
+
+    fn combine(&self, other: &CandidateSet, op: impl Fn(u64, u64) -> u64) -> Self {
+        assert!(
+            self.space == other.space && self.scheme == other.scheme,
+            "candidate sets for different rules"
+        );
+        CandidateSet {
+            space: self.space.clone(),
+            scheme: self.scheme,
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(&a, &b)| op(a, b))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! Consistency as a constraint problem, for rules with too many codes to
+//! list.
+//!
+//! Symbols in common with a guess only depend on how many times the code
+//! uses each symbol, so the search first picks those counts, keeping each
+//! turn's total of exact and misplaced symbols reachable. It then fills
+//! positions with the counts picked, the one with the fewest symbols left
+//! first, keeping each turn's exact matches reachable. Both steps start from
+//! the bounds [`Deductions`] derives from the history, and backtrack as soon
+//! as a turn can no longer get its feedback.
+//!
+//! When the feedback only counts exact matches there are no counts to pick,
+//! so the positions are filled straight away. Each open position can then
+//! add no more matches across the turns than its best symbol, and no fewer
+//! than its worst, and states already found to lead nowhere are skipped.
+
+use std::collections::HashSet;
+
+use crate::{
+    deduction::Deductions,
+    game::{Code, Turn},
+    rules::{CodeLen, Rules},
+    solver::{self, Solver},
+};
+
+/// Plays a code consistent with the history, found by a backtracking search
+/// instead of going through every code.
+#[derive(Debug, Default)]
+pub struct ConstraintSolver;
+
+impl Solver for ConstraintSolver {
+    fn next_guess(&mut self, rules: &Rules, history: &[Turn]) -> Option<Code> {
+        find_consistent(rules, history)
+    }
+}
+
+/// Some code consistent with every turn, or `None` if there is none.
+pub fn find_consistent(rules: &Rules, history: &[Turn]) -> Option<Code> {
+    let code_len = rules.code_len() as usize;
+    if history.iter().any(|turn| turn.guess().len() != code_len) {
+        return None;
+    }
+    let deductions = Deductions::new(rules, history);
+    if deductions.is_contradictory() {
+        return None;
+    }
+    let mut search = Search::new(rules, history, &deductions);
+    // Without misplaced symbols, the counts are better left to the positions
+    let found = if search.targets.iter().all(|t| t.total.is_some()) {
+        search.pick_counts(0, 0)
+    } else {
+        search.fill(0)
+    };
+    found.then_some(search.code)
+}
+
+/// What a turn requires of the secret, and how far the code being built is
+/// from it.
+struct Target {
+    guess: Code,
+    guess_counts: Vec<CodeLen>,
+    exact: usize,
+    /// Exact plus misplaced, unless the scheme hides misplaced symbols.
+    total: Option<usize>,
+    /// Whether the secret can match the guess at each position.
+    matchable: Vec<bool>,
+    exact_so_far: usize,
+    matched_so_far: usize,
+}
+
+struct Search<'a> {
+    rules: &'a Rules,
+    history: &'a [Turn],
+    domains: Vec<Vec<u8>>,
+    /// Fewest and most copies of each symbol the code may use, narrowed to
+    /// exact counts once they are picked.
+    min_counts: Vec<CodeLen>,
+    max_counts: Vec<CodeLen>,
+    targets: Vec<Target>,
+    code: Code,
+    /// Positions not set yet.
+    open: Vec<bool>,
+    counts: Vec<CodeLen>,
+    /// Per-symbol tallies reused by the exact bound.
+    scratch: Vec<usize>,
+    /// States already found to lead nowhere. Only kept when every turn has
+    /// exact matches alone, as the rest of the search then depends on
+    /// nothing else.
+    dead_ends: Option<HashSet<Vec<usize>>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(rules: &'a Rules, history: &'a [Turn], deductions: &Deductions) -> Self {
+        let code_len = rules.code_len() as usize;
+        let symbols = rules.available_symbols();
+        let domains: Vec<Vec<u8>> = (0..code_len)
+            .map(|position| deductions.possible_symbols(position))
+            .collect();
+        let targets = history
+            .iter()
+            .map(|turn| {
+                let guess = turn.guess().clone();
+                let mut guess_counts = vec![0; u8::MAX as usize + 1];
+                for &symbol in &guess {
+                    guess_counts[symbol as usize] += 1;
+                }
+                let matchable = (0..code_len)
+                    .map(|position| domains[position].contains(&guess[position]))
+                    .collect();
+                let exact = turn.feedback().exact() as usize;
+                Target {
+                    guess,
+                    guess_counts,
+                    exact,
+                    total: turn.feedback().misplaced().map(|m| exact + m as usize),
+                    matchable,
+                    exact_so_far: 0,
+                    matched_so_far: 0,
+                }
+            })
+            .collect();
+        let (min_counts, max_counts) = (0..symbols).map(|s| deductions.count_bounds(s)).unzip();
+        let exact_only = history.iter().all(|t| t.feedback().misplaced().is_none());
+        Search {
+            rules,
+            history,
+            domains,
+            min_counts,
+            max_counts,
+            targets,
+            code: vec![0; code_len],
+            open: vec![true; code_len],
+            counts: vec![0; symbols as usize],
+            scratch: vec![0; symbols as usize],
+            dead_ends: exact_only.then(HashSet::new),
+        }
+    }
+
+    /// Tries every count of `symbol`, with `used` positions taken by the
+    /// symbols before it, then picks the counts of the symbols after it.
+    fn pick_counts(&mut self, symbol: usize, used: usize) -> bool {
+        let code_len = self.domains.len();
+        let symbols = self.max_counts.len();
+        if symbol == symbols {
+            return used == code_len && self.fill(0);
+        }
+        let (min, max) = (self.min_counts[symbol], self.max_counts[symbol]);
+        let later_max: usize = self.max_counts[symbol + 1..]
+            .iter()
+            .map(|&c| c as usize)
+            .sum();
+        let later_min: usize = self.min_counts[symbol + 1..]
+            .iter()
+            .map(|&c| c as usize)
+            .sum();
+        for count in min..=max {
+            let left = code_len as isize - used as isize - count as isize;
+            if left < later_min as isize {
+                break;
+            }
+            if left as usize > later_max {
+                continue;
+            }
+            let left = left as usize;
+            let fits = self.targets.iter().all(|target| {
+                let total = target.total.expect("counts are only picked with totals");
+                let matched =
+                    target.matched_so_far + target.guess_counts[symbol].min(count) as usize;
+                let later: usize = target.guess_counts[symbol + 1..symbols]
+                    .iter()
+                    .map(|&c| c as usize)
+                    .sum();
+                matched <= total && total <= matched + left.min(later)
+            });
+            if !fits {
+                continue;
+            }
+            for target in &mut self.targets {
+                target.matched_so_far += target.guess_counts[symbol].min(count) as usize;
+            }
+            self.min_counts[symbol] = count;
+            self.max_counts[symbol] = count;
+            let found = self.pick_counts(symbol + 1, used + count as usize);
+            self.min_counts[symbol] = min;
+            self.max_counts[symbol] = max;
+            for target in &mut self.targets {
+                target.matched_so_far -= target.guess_counts[symbol].min(count) as usize;
+            }
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Fills the open position with the fewest symbols left, then the
+    /// others, with `filled` positions already set.
+    fn fill(&mut self, filled: usize) -> bool {
+        if filled == self.domains.len() {
+            // The bounds only prune, so the full feedback is checked here
+            return solver::is_consistent(self.rules, &self.code, self.history);
+        }
+        let state = self.dead_ends.as_ref().map(|_| self.state());
+        if let (Some(dead_ends), Some(state)) = (&self.dead_ends, &state)
+            && dead_ends.contains(state)
+        {
+            return false;
+        }
+        let Some((position, symbols)) = self.most_constrained() else {
+            return false;
+        };
+        let picked = self.targets.iter().all(|t| t.total.is_some());
+        for symbol in symbols {
+            self.place(position, symbol, true, picked);
+            if self.feasible(picked) && self.fill(filled + 1) {
+                return true;
+            }
+            self.place(position, symbol, false, picked);
+        }
+        if let (Some(dead_ends), Some(state)) = (&mut self.dead_ends, state) {
+            dead_ends.insert(state);
+        }
+        false
+    }
+
+    /// The open positions, each turn's exact matches and the copies used of
+    /// each symbol whose count is bounded.
+    fn state(&self) -> Vec<usize> {
+        let code_len = self.domains.len() as CodeLen;
+        let open = self.open.iter().map(|&open| open as usize);
+        let exact = self.targets.iter().map(|target| target.exact_so_far);
+        let counts = (0..self.counts.len())
+            .filter(|&s| self.min_counts[s] > 0 || self.max_counts[s] < code_len)
+            .map(|s| self.counts[s] as usize);
+        open.chain(exact).chain(counts).collect()
+    }
+
+    /// Puts `symbol` at `position`, or takes it back. Symbols in common are
+    /// only tracked while counts are not picked.
+    fn place(&mut self, position: usize, symbol: u8, put: bool, picked: bool) {
+        let s = symbol as usize;
+        if put {
+            self.code[position] = symbol;
+            self.open[position] = false;
+        } else {
+            self.open[position] = true;
+            self.counts[s] -= 1;
+        }
+        for target in &mut self.targets {
+            let exact = (target.guess[position] == symbol) as usize;
+            // One more copy of the symbol matches if the guess has spare ones
+            let matched = (!picked && self.counts[s] < target.guess_counts[s]) as usize;
+            if put {
+                target.exact_so_far += exact;
+                target.matched_so_far += matched;
+            } else {
+                target.exact_so_far -= exact;
+                target.matched_so_far -= matched;
+            }
+        }
+        if put {
+            self.counts[s] += 1;
+        }
+    }
+
+    /// Returns `false` if no way of filling the open positions can satisfy
+    /// every turn.
+    fn feasible(&mut self, picked: bool) -> bool {
+        let code_len = self.domains.len();
+        let left = self.open.iter().filter(|&&open| open).count();
+        let missing: usize = self
+            .min_counts
+            .iter()
+            .zip(&self.counts)
+            .map(|(&min, &count)| min.saturating_sub(count) as usize)
+            .sum();
+        if missing > left {
+            return false;
+        }
+        for target in &self.targets {
+            // Each symbol can add as many exact matches as the open positions
+            // where the guess has it, and the copies of it left
+            self.scratch.iter_mut().for_each(|tally| *tally = 0);
+            for position in 0..code_len {
+                if self.open[position] && target.matchable[position] {
+                    self.scratch[target.guess[position] as usize] += 1;
+                }
+            }
+            let reachable: usize = self
+                .scratch
+                .iter()
+                .enumerate()
+                .map(|(s, &tally)| tally.min((self.max_counts[s] - self.counts[s]) as usize))
+                .sum();
+            if target.exact_so_far > target.exact || target.exact > target.exact_so_far + reachable
+            {
+                return false;
+            }
+            if !picked && let Some(total) = target.total {
+                let spare = code_len - target.matched_so_far;
+                if target.matched_so_far > total || total > target.matched_so_far + left.min(spare)
+                {
+                    return false;
+                }
+            }
+        }
+        picked || self.matches_reachable()
+    }
+
+    /// Returns `false` if the open positions cannot make up the exact
+    /// matches the turns still need, each position adding a match to every
+    /// turn whose guess has its symbol there.
+    ///
+    /// Symbols that would give a turn more matches than it has are left out,
+    /// and each position counts with its best and its worst symbol left.
+    fn matches_reachable(&self) -> bool {
+        let code_len = self.domains.len();
+        let needed: usize = self
+            .targets
+            .iter()
+            .map(|target| target.exact - target.exact_so_far)
+            .sum();
+        let (mut fewest, mut most) = (0, 0);
+        for position in (0..code_len).filter(|&p| self.open[p]) {
+            let mut range: Option<(usize, usize)> = None;
+            for &symbol in &self.domains[position] {
+                if self.counts[symbol as usize] >= self.max_counts[symbol as usize] {
+                    continue;
+                }
+                let mut matched = 0;
+                let mut allowed = true;
+                for target in &self.targets {
+                    if target.guess[position] == symbol {
+                        allowed &= target.exact_so_far < target.exact;
+                        matched += 1;
+                    }
+                }
+                if allowed {
+                    range = Some(match range {
+                        Some((low, high)) => (low.min(matched), high.max(matched)),
+                        None => (matched, matched),
+                    });
+                }
+            }
+            let Some((low, high)) = range else {
+                return false;
+            };
+            fewest += low;
+            most += high;
+        }
+        fewest <= needed && needed <= most
+    }
+
+    /// The open position with the fewest symbols left, and those symbols.
+    ///
+    /// Turns with all their exact matches must not get another one, and turns
+    /// needing a match at every open position they can match must get them
+    /// all.
+    ///
+    /// # Returns
+    ///
+    /// `None` if some open position has no symbol left.
+    fn most_constrained(&self) -> Option<(usize, Vec<u8>)> {
+        let code_len = self.domains.len();
+        let mut needs: Vec<Option<u8>> = vec![None; code_len];
+        for target in &self.targets {
+            let matchable = (0..code_len)
+                .filter(|&p| self.open[p] && target.matchable[p])
+                .count();
+            if target.exact_so_far + matchable != target.exact {
+                continue;
+            }
+            for (position, need) in needs.iter_mut().enumerate() {
+                if !self.open[position] || !target.matchable[position] {
+                    continue;
+                }
+                let symbol = target.guess[position];
+                match need {
+                    Some(needed) if *needed != symbol => return None,
+                    _ => *need = Some(symbol),
+                }
+            }
+        }
+
+        let mut best: Option<(usize, Vec<u8>)> = None;
+        for position in (0..code_len).filter(|&p| self.open[p]) {
+            let symbols: Vec<u8> = self.domains[position]
+                .iter()
+                .copied()
+                .filter(|&symbol| needs[position].is_none_or(|needed| needed == symbol))
+                .filter(|&symbol| {
+                    self.counts[symbol as usize] < self.max_counts[symbol as usize]
+                        && self.targets.iter().all(|target| {
+                            target.exact_so_far < target.exact || target.guess[position] != symbol
+                        })
+                })
+                .collect();
+            if symbols.is_empty() {
+                return None;
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(_, fewest)| symbols.len() < fewest.len())
+            {
+                best = Some((position, symbols));
+            }
+        }
+        best
+    }
+}
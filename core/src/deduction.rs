@@ -0,0 +1,360 @@
+use crate::{
+    feedback::{Feedback, Mark},
+    game::{Code, Turn},
+    rules::{CodeLen, Rules},
+};
+
+/// A fact derived from the history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fact {
+    /// The symbol does not appear in the secret.
+    Absent { symbol: u8 },
+    /// The position holds one of the listed symbols.
+    PositionOneOf { position: usize, symbols: Vec<u8> },
+    /// The symbol appears between `min` and `max` times in the secret.
+    SymbolCount {
+        symbol: u8,
+        min: CodeLen,
+        max: CodeLen,
+    },
+}
+
+/// What constraint propagation over the history tells about the secret.
+///
+/// The deductions are sound but not complete: every code consistent with the
+/// history is admitted, but some admitted codes may still be inconsistent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deductions {
+    possible: Vec<Vec<bool>>,
+    min_count: Vec<CodeLen>,
+    max_count: Vec<CodeLen>,
+    contradiction: bool,
+    rounds: usize,
+}
+
+impl Deductions {
+    /// Propagates the constraints of every turn until nothing more can be derived.
+    pub fn new(rules: &Rules, history: &[Turn]) -> Self {
+        let code_len = rules.code_len();
+        let symbols = rules.available_symbols() as usize;
+        let max_count = if rules.allows_repetition() {
+            code_len
+        } else {
+            1
+        };
+        let mut deductions = Deductions {
+            possible: vec![vec![true; symbols]; code_len as usize],
+            min_count: vec![0; symbols],
+            max_count: vec![max_count; symbols],
+            contradiction: false,
+            rounds: 0,
+        };
+        // No code of the right length can answer a guess of another length
+        if history
+            .iter()
+            .any(|turn| turn.guess().len() != code_len as usize)
+        {
+            deductions.contradiction = true;
+            return deductions;
+        }
+        // Symbols outside the rules never match, so such guesses tell nothing more.
+        let history: Vec<&Turn> = history
+            .iter()
+            .filter(|turn| turn.guess().iter().all(|&s| (s as usize) < symbols))
+            .collect();
+
+        loop {
+            let before = deductions.clone();
+            deductions.propagate_counts();
+            for turn in &history {
+                deductions.propagate_turn(turn);
+            }
+            deductions.check();
+            if deductions.contradiction || deductions.same_facts(&before) {
+                break;
+            }
+            deductions.rounds += 1;
+        }
+        deductions
+    }
+
+    /// Returns `true` if no code can satisfy the history.
+    pub fn is_contradictory(&self) -> bool {
+        self.contradiction
+    }
+
+    /// Number of propagation rounds that derived something new.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    pub fn is_possible(&self, position: usize, symbol: u8) -> bool {
+        self.possible[position][symbol as usize]
+    }
+
+    pub fn possible_symbols(&self, position: usize) -> Vec<u8> {
+        (0..self.possible[position].len() as u8)
+            .filter(|&symbol| self.is_possible(position, symbol))
+            .collect()
+    }
+
+    /// Lower and upper bound on how many times `symbol` appears in the secret.
+    pub fn count_bounds(&self, symbol: u8) -> (CodeLen, CodeLen) {
+        (
+            self.min_count[symbol as usize],
+            self.max_count[symbol as usize],
+        )
+    }
+
+    pub fn is_absent(&self, symbol: u8) -> bool {
+        self.max_count[symbol as usize] == 0
+    }
+
+    /// Returns `true` if `code` agrees with every deduction.
+    pub fn admits(&self, code: &Code) -> bool {
+        if self.contradiction || code.len() != self.possible.len() {
+            return false;
+        }
+        let symbols = self.min_count.len();
+        if code.iter().any(|&s| s as usize >= symbols) {
+            return false;
+        }
+        let counts = symbol_counts(code, symbols);
+        code.iter()
+            .enumerate()
+            .all(|(position, &symbol)| self.is_possible(position, symbol))
+            && (0..symbols)
+                .all(|s| self.min_count[s] <= counts[s] && counts[s] <= self.max_count[s])
+    }
+
+    /// Lists every code admitted by the deductions, in lexicographic order.
+    pub fn admitted_codes(&self) -> Vec<Code> {
+        let mut codes = Vec::new();
+        if self.contradiction {
+            return codes;
+        }
+        let choices: Vec<Vec<u8>> = (0..self.possible.len())
+            .map(|position| self.possible_symbols(position))
+            .collect();
+        let mut indices = vec![0; choices.len()];
+        loop {
+            let code: Code = indices
+                .iter()
+                .zip(&choices)
+                .map(|(&index, symbols)| symbols[index])
+                .collect();
+            if self.admits(&code) {
+                codes.push(code);
+            }
+            let mut position = choices.len();
+            loop {
+                if position == 0 {
+                    return codes;
+                }
+                position -= 1;
+                indices[position] += 1;
+                if indices[position] < choices[position].len() {
+                    break;
+                }
+                indices[position] = 0;
+            }
+        }
+    }
+
+    /// Summarizes the deductions as facts, leaving out what is already obvious
+    /// from the rules.
+    pub fn facts(&self) -> Vec<Fact> {
+        let code_len = self.possible.len() as CodeLen;
+        let symbols = self.min_count.len();
+        let mut facts = Vec::new();
+        for symbol in 0..symbols as u8 {
+            if self.is_absent(symbol) {
+                facts.push(Fact::Absent { symbol });
+            }
+        }
+        for position in 0..self.possible.len() {
+            let possible = self.possible_symbols(position);
+            let narrowed = possible
+                .iter()
+                .filter(|&&symbol| !self.is_absent(symbol))
+                .count()
+                < (0..symbols as u8).filter(|&s| !self.is_absent(s)).count();
+            if narrowed {
+                facts.push(Fact::PositionOneOf {
+                    position,
+                    symbols: possible,
+                });
+            }
+        }
+        for symbol in 0..symbols as u8 {
+            let (min, max) = self.count_bounds(symbol);
+            if max > 0 && (min > 0 || max < code_len) {
+                facts.push(Fact::SymbolCount { symbol, min, max });
+            }
+        }
+        facts
+    }
+
+    /// Bounds symbol counts by the positions they can take, and by the code length.
+    fn propagate_counts(&mut self) {
+        let code_len = self.possible.len() as CodeLen;
+        let symbols = self.min_count.len();
+        for s in 0..symbols {
+            let places = self.possible.iter().filter(|p| p[s]).count() as CodeLen;
+            let fixed = self
+                .possible
+                .iter()
+                .filter(|p| p[s] && p.iter().filter(|&&b| b).count() == 1)
+                .count() as CodeLen;
+            self.max_count[s] = self.max_count[s].min(places);
+            self.min_count[s] = self.min_count[s].max(fixed);
+            if self.max_count[s] == 0 {
+                self.possible.iter_mut().for_each(|p| p[s] = false);
+            } else if self.min_count[s] == places {
+                for p in self.possible.iter_mut().filter(|p| p[s]) {
+                    p.iter_mut().for_each(|b| *b = false);
+                    p[s] = true;
+                }
+            }
+        }
+
+        let min_total: usize = self.min_count.iter().map(|&c| c as usize).sum();
+        let max_total: usize = self.max_count.iter().map(|&c| c as usize).sum();
+        for s in 0..symbols {
+            let others_max = max_total - self.max_count[s] as usize;
+            let others_min = min_total - self.min_count[s] as usize;
+            let lower = (code_len as usize).saturating_sub(others_max) as CodeLen;
+            let upper = (code_len as usize).saturating_sub(others_min) as CodeLen;
+            self.min_count[s] = self.min_count[s].max(lower);
+            self.max_count[s] = self.max_count[s].min(upper);
+        }
+    }
+
+    /// Applies the exact and total match counts of one turn, and its marks if any.
+    fn propagate_turn(&mut self, turn: &Turn) {
+        let guess = turn.guess();
+        let exact = turn.feedback().exact() as usize;
+        if let Feedback::Marks(marks) = turn.feedback() {
+            self.propagate_marks(guess, marks);
+        }
+
+        let maybe: Vec<usize> = (0..guess.len())
+            .filter(|&i| self.possible[i][guess[i] as usize])
+            .collect();
+        let sure: Vec<usize> = maybe
+            .iter()
+            .copied()
+            .filter(|&i| self.possible[i].iter().filter(|&&b| b).count() == 1)
+            .collect();
+        if maybe.len() < exact || sure.len() > exact {
+            self.contradiction = true;
+            return;
+        }
+        if maybe.len() == exact {
+            for &i in &maybe {
+                self.possible[i].iter_mut().for_each(|b| *b = false);
+                self.possible[i][guess[i] as usize] = true;
+            }
+        } else if sure.len() == exact {
+            for &i in maybe.iter().filter(|i| !sure.contains(i)) {
+                self.possible[i][guess[i] as usize] = false;
+            }
+        }
+
+        // The exact-only scheme tells nothing about misplaced symbols
+        let Some(misplaced) = turn.feedback().misplaced() else {
+            return;
+        };
+        let total = exact + misplaced as usize;
+        let guess_counts = symbol_counts(guess, self.min_count.len());
+        let used: Vec<usize> = (0..guess_counts.len())
+            .filter(|&s| guess_counts[s] > 0)
+            .collect();
+        let matched_min: usize = used
+            .iter()
+            .map(|&s| matched(guess_counts[s], self.min_count[s]) as usize)
+            .sum();
+        let matched_max: usize = used
+            .iter()
+            .map(|&s| matched(guess_counts[s], self.max_count[s]) as usize)
+            .sum();
+        if total < matched_min || total > matched_max {
+            self.contradiction = true;
+            return;
+        }
+        for &s in &used {
+            let at_least = total + matched(guess_counts[s], self.max_count[s]) as usize;
+            let at_least = at_least.saturating_sub(matched_max);
+            let at_most = total + matched(guess_counts[s], self.min_count[s]) as usize;
+            let at_most = at_most - matched_min;
+            while (matched(guess_counts[s], self.min_count[s]) as usize) < at_least {
+                self.min_count[s] += 1;
+            }
+            while (matched(guess_counts[s], self.max_count[s]) as usize) > at_most {
+                self.max_count[s] -= 1;
+            }
+        }
+    }
+
+    /// Applies what each mark tells about its own position and symbol.
+    fn propagate_marks(&mut self, guess: &Code, marks: &[Mark]) {
+        for (position, (&symbol, &mark)) in guess.iter().zip(marks).enumerate() {
+            if mark == Mark::Correct {
+                for (s, possible) in self.possible[position].iter_mut().enumerate() {
+                    *possible &= s == symbol as usize;
+                }
+            } else {
+                self.possible[position][symbol as usize] = false;
+            }
+        }
+        for &symbol in guess {
+            let s = symbol as usize;
+            let marked = guess
+                .iter()
+                .zip(marks)
+                .filter(|&(&g, &m)| g == symbol && m != Mark::Absent)
+                .count() as CodeLen;
+            self.min_count[s] = self.min_count[s].max(marked);
+            // An absent mark means the secret has no more of this symbol
+            let absent = guess
+                .iter()
+                .zip(marks)
+                .any(|(&g, &m)| g == symbol && m == Mark::Absent);
+            if absent {
+                self.max_count[s] = self.max_count[s].min(marked);
+            }
+        }
+    }
+
+    fn check(&mut self) {
+        let empty_position = self.possible.iter().any(|p| !p.contains(&true));
+        let empty_count = self
+            .min_count
+            .iter()
+            .zip(&self.max_count)
+            .any(|(min, max)| min > max);
+        if empty_position || empty_count {
+            self.contradiction = true;
+        }
+    }
+
+    fn same_facts(&self, other: &Deductions) -> bool {
+        self.possible == other.possible
+            && self.min_count == other.min_count
+            && self.max_count == other.max_count
+    }
+}
+
+/// How many pegs of a symbol are matched (exact or misplaced), given how many
+/// times the guess and the secret use it.
+fn matched(guess_count: CodeLen, secret_count: CodeLen) -> CodeLen {
+    guess_count.min(secret_count)
+}
+
+fn symbol_counts(code: &Code, symbols: usize) -> Vec<CodeLen> {
+    let mut counts = vec![0; symbols];
+    for &symbol in code {
+        counts[symbol as usize] += 1;
+    }
+    counts
+}
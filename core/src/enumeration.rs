@@ -0,0 +1,169 @@
+//! Codes allowed by the rules, listed one at a time or numbered by rank.
+//!
+//! Both follow the lexicographic order of the codes, so the code of rank `r`
+//! is the `r`-th code yielded by [`Codes`].
+
+use crate::{game::Code, rules::Rules};
+
+/// Streams every code allowed by the rules in lexicographic order, without
+/// ever holding more than one code.
+#[derive(Debug, Clone)]
+pub struct Codes {
+    next: Option<Code>,
+    symbols: u8,
+    repetition: bool,
+}
+
+impl Codes {
+    pub fn new(rules: &Rules) -> Self {
+        Self::starting(
+            rules.code_len() as usize,
+            rules.available_symbols(),
+            rules.allows_repetition(),
+        )
+    }
+
+    fn starting(code_len: usize, symbols: u8, repetition: bool) -> Self {
+        let mut first = vec![0; code_len];
+        let next = fill_smallest(&mut first, 0, symbols, repetition).then_some(first);
+        Codes {
+            next,
+            symbols,
+            repetition,
+        }
+    }
+}
+
+impl Iterator for Codes {
+    type Item = Code;
+
+    fn next(&mut self) -> Option<Code> {
+        let code = self.next.take()?;
+        let mut successor = code.clone();
+        // Bump the rightmost position that can take a larger symbol, then
+        // refill the positions after it as low as possible.
+        for position in (0..successor.len()).rev() {
+            let larger = (successor[position] + 1..self.symbols)
+                .find(|s| self.repetition || !successor[..position].contains(s));
+            if let Some(symbol) = larger {
+                successor[position] = symbol;
+                if fill_smallest(&mut successor, position + 1, self.symbols, self.repetition) {
+                    self.next = Some(successor);
+                    break;
+                }
+            }
+        }
+        Some(code)
+    }
+}
+
+/// Numbers the codes allowed by the rules from 0, in lexicographic order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeSpace {
+    code_len: usize,
+    symbols: u8,
+    repetition: bool,
+    size: u64,
+}
+
+impl CodeSpace {
+    /// # Returns
+    ///
+    /// `None` if there are more codes than a `u64` can number.
+    pub fn new(rules: &Rules) -> Option<Self> {
+        let mut space = CodeSpace {
+            code_len: rules.code_len() as usize,
+            symbols: rules.available_symbols(),
+            repetition: rules.allows_repetition(),
+            size: 0,
+        };
+        space.size = space.completions(0)?;
+        Some(space)
+    }
+
+    /// Number of codes allowed by the rules.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn iter(&self) -> Codes {
+        Codes::starting(self.code_len, self.symbols, self.repetition)
+    }
+
+    /// Position of the code in lexicographic order.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the rules do not allow the code.
+    pub fn rank(&self, code: &[u8]) -> Option<u64> {
+        if code.len() != self.code_len {
+            return None;
+        }
+        let mut rank = 0;
+        for (position, &symbol) in code.iter().enumerate() {
+            if symbol >= self.symbols {
+                return None;
+            }
+            let prefix = &code[..position];
+            if !self.repetition && prefix.contains(&symbol) {
+                return None;
+            }
+            let smaller = (0..symbol)
+                .filter(|s| self.repetition || !prefix.contains(s))
+                .count() as u64;
+            // Every code is counted by `size`, so this cannot overflow
+            rank += smaller * self.completions(position + 1)?;
+        }
+        Some(rank)
+    }
+
+    /// The code at a position in lexicographic order.
+    ///
+    /// # Returns
+    ///
+    /// `None` if `rank` is not below [`CodeSpace::size`].
+    pub fn unrank(&self, mut rank: u64) -> Option<Code> {
+        if rank >= self.size {
+            return None;
+        }
+        let mut code = Code::with_capacity(self.code_len);
+        for position in 0..self.code_len {
+            let block = self.completions(position + 1)?;
+            let index = (rank / block) as usize;
+            rank %= block;
+            let symbol = (0..self.symbols)
+                .filter(|s| self.repetition || !code.contains(s))
+                .nth(index)?;
+            code.push(symbol);
+        }
+        Some(code)
+    }
+
+    /// Number of ways to complete a code whose first `filled` positions are set.
+    fn completions(&self, filled: usize) -> Option<u64> {
+        (filled..self.code_len).try_fold(1u64, |count, position| {
+            let choices = if self.repetition {
+                self.symbols as u64
+            } else {
+                (self.symbols as u64).checked_sub(position as u64)?
+            };
+            count.checked_mul(choices)
+        })
+    }
+}
+
+/// Sets the positions from `start` on to the smallest symbols allowed.
+///
+/// # Returns
+///
+/// `false` if there are not enough unused symbols left.
+fn fill_smallest(code: &mut Code, start: usize, symbols: u8, repetition: bool) -> bool {
+    for position in start..code.len() {
+        let smallest = (0..symbols).find(|s| repetition || !code[..position].contains(s));
+        match smallest {
+            Some(symbol) => code[position] = symbol,
+            None => return false,
+        }
+    }
+    true
+}
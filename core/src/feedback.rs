@@ -1,19 +1,229 @@
-use crate::{game::Code, rules::CodeLen};
+use crate::{
+    game::Code,
+    rules::{CodeLen, Rules},
+    scheme::Scheme,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Feedback {
-    exact: CodeLen,
-    misplaced: CodeLen,
+#[derive(Debug, PartialEq, Eq)]
+pub enum FeedbackError {
+    /// More pegs than positions in the code.
+    TooManyPegs,
+    /// No secret and guess allowed by the rules produce this feedback.
+    Unreachable,
+    /// The feedback is not of the kind given by the rules' scheme.
+    WrongScheme,
+}
+
+/// Per-position mark of the Wordle-style scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Mark {
+    /// The symbol is at this position in the secret.
+    Correct,
+    /// The symbol is elsewhere in the secret, and not already accounted for.
+    Present,
+    Absent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Feedback {
+    /// Black and white pegs, or bulls and cows.
+    Pegs { exact: CodeLen, misplaced: CodeLen },
+    /// Black pegs only.
+    Exact { exact: CodeLen },
+    /// One mark per position.
+    Marks(Vec<Mark>),
 }
 
 impl Feedback {
+    /// Scores a guess with classic black and white pegs.
     pub fn new(secret: &Code, guess: &Code) -> Self {
-        let exact = secret
-            .iter()
-            .zip(guess.iter())
-            .filter(|&(s, g)| s == g)
-            .count() as CodeLen;
-        let misplaced = secret.iter().filter(|s| guess.contains(s)).count() as CodeLen - exact;
-        Feedback { exact, misplaced }
+        let (exact, matched) = peg_counts(secret, guess);
+        Feedback::Pegs {
+            exact,
+            misplaced: matched - exact,
+        }
+    }
+
+    /// Builds a feedback from raw peg counts, e.g. as entered by a human codemaker.
+    ///
+    /// # Returns
+    ///
+    /// An error if the rules' scheme does not use pegs, or if no secret and
+    /// guess allowed by the rules produce these counts.
+    pub fn from_counts(
+        exact: CodeLen,
+        misplaced: CodeLen,
+        rules: &Rules,
+    ) -> Result<Self, FeedbackError> {
+        if exact as u16 + misplaced as u16 > rules.code_len() as u16 {
+            return Err(FeedbackError::TooManyPegs);
+        }
+        Feedback::Pegs { exact, misplaced }.checked(rules)
+    }
+
+    /// Builds a feedback of the exact-only scheme from its black peg count.
+    pub fn from_exact(exact: CodeLen, rules: &Rules) -> Result<Self, FeedbackError> {
+        if exact > rules.code_len() {
+            return Err(FeedbackError::TooManyPegs);
+        }
+        Feedback::Exact { exact }.checked(rules)
+    }
+
+    /// Builds a feedback of the Wordle-style scheme from its marks.
+    pub fn from_marks(marks: Vec<Mark>, rules: &Rules) -> Result<Self, FeedbackError> {
+        Feedback::Marks(marks).checked(rules)
+    }
+
+    /// Returns `true` if some secret and guess allowed by the rules produce this
+    /// feedback under the rules' scheme.
+    pub fn is_valid_for(&self, rules: &Rules) -> bool {
+        let code_len = rules.code_len();
+        let symbols = rules.available_symbols();
+        match (rules.scheme(), self) {
+            (Scheme::Classic, Feedback::Pegs { exact, misplaced }) => {
+                *exact <= code_len
+                    && *misplaced <= code_len - exact
+                    && pegs_reachable(code_len - exact, *misplaced, symbols)
+            }
+            (Scheme::BullsAndCows, Feedback::Pegs { exact, misplaced }) => {
+                // Codes without repeats share at least 2n - k symbols
+                let shared = *exact as u16 + *misplaced as u16;
+                shared <= code_len as u16
+                    && shared >= (2 * code_len as u16).saturating_sub(symbols as u16)
+                    && !(*exact as u16 + 1 == code_len as u16 && *misplaced == 1)
+            }
+            (Scheme::ExactOnly, Feedback::Exact { exact }) => {
+                *exact <= code_len && (symbols > 1 || *exact == code_len)
+            }
+            (Scheme::Wordle, Feedback::Marks(marks)) => {
+                marks.len() == code_len as usize && marks_reachable(marks, symbols)
+            }
+            _ => false,
+        }
+    }
+
+    /// Lists every feedback the rules' scheme can produce.
+    pub fn all_possible(rules: &Rules) -> Vec<Feedback> {
+        rules.scheme().all_possible(rules)
+    }
+
+    /// Number of pegs or marks for symbols at the right position.
+    pub fn exact(&self) -> CodeLen {
+        match self {
+            Feedback::Pegs { exact, .. } | Feedback::Exact { exact } => *exact,
+            Feedback::Marks(marks) => {
+                marks.iter().filter(|&&m| m == Mark::Correct).count() as CodeLen
+            }
+        }
+    }
+
+    /// Number of pegs or marks for symbols at the wrong position, unless the
+    /// scheme hides them.
+    pub fn misplaced(&self) -> Option<CodeLen> {
+        match self {
+            Feedback::Pegs { misplaced, .. } => Some(*misplaced),
+            Feedback::Exact { .. } => None,
+            Feedback::Marks(marks) => {
+                Some(marks.iter().filter(|&&m| m == Mark::Present).count() as CodeLen)
+            }
+        }
+    }
+
+    /// How many pegs or marks differ from another feedback of the same scheme.
+    pub(crate) fn distance(&self, other: &Feedback) -> u32 {
+        match (self, other) {
+            (Feedback::Marks(a), Feedback::Marks(b)) => {
+                a.iter().zip(b).filter(|(x, y)| x != y).count() as u32
+            }
+            _ => {
+                let misplaced = self
+                    .misplaced()
+                    .unwrap_or(0)
+                    .abs_diff(other.misplaced().unwrap_or(0));
+                self.exact().abs_diff(other.exact()) as u32 + misplaced as u32
+            }
+        }
+    }
+
+    /// Checks that the feedback is of the kind given by the rules' scheme and
+    /// can be produced under the rules.
+    pub fn validate(&self, rules: &Rules) -> Result<(), FeedbackError> {
+        let kind_matches = matches!(
+            (rules.scheme(), self),
+            (
+                Scheme::Classic | Scheme::BullsAndCows,
+                Feedback::Pegs { .. }
+            ) | (Scheme::ExactOnly, Feedback::Exact { .. })
+                | (Scheme::Wordle, Feedback::Marks(_))
+        );
+        if !kind_matches {
+            return Err(FeedbackError::WrongScheme);
+        }
+        if !self.is_valid_for(rules) {
+            return Err(FeedbackError::Unreachable);
+        }
+        Ok(())
+    }
+
+    fn checked(self, rules: &Rules) -> Result<Self, FeedbackError> {
+        self.validate(rules).map(|_| self)
+    }
+}
+
+/// Counts the pegs at the right position, and all the pegs matched once each.
+pub(crate) fn peg_counts(secret: &Code, guess: &Code) -> (CodeLen, CodeLen) {
+    let exact = secret
+        .iter()
+        .zip(guess.iter())
+        .filter(|&(s, g)| s == g)
+        .count() as CodeLen;
+    // Each guess peg can match at most one secret peg of the same symbol
+    let mut unmatched = guess.clone();
+    let mut matched: CodeLen = 0;
+    for symbol in secret {
+        if let Some(index) = unmatched.iter().position(|g| g == symbol) {
+            unmatched.swap_remove(index);
+            matched += 1;
+        }
+    }
+    (exact, matched)
+}
+
+/// Returns `true` if `misplaced` pegs can come from `rest` positions where
+/// guess and secret differ.
+fn pegs_reachable(rest: CodeLen, misplaced: CodeLen, symbols: u8) -> bool {
+    // The guess and secret symbols at those positions share `misplaced` symbols
+    match symbols {
+        1 => rest == 0,
+        2 => misplaced.is_multiple_of(2),
+        _ => !(rest == 1 && misplaced == 1),
+    }
+}
+
+/// Returns `true` if some secret and guess give these marks.
+fn marks_reachable(marks: &[Mark], symbols: u8) -> bool {
+    let rest: Vec<Mark> = marks
+        .iter()
+        .copied()
+        .filter(|&m| m != Mark::Correct)
+        .collect();
+    let present = rest.iter().filter(|&&m| m == Mark::Present).count();
+    if !pegs_reachable(rest.len() as CodeLen, present as CodeLen, symbols) {
+        return false;
+    }
+    if symbols != 2 {
+        return true;
+    }
+    // With two symbols, the absent marks all belong to the symbol the guess
+    // uses more often, and its present marks come first.
+    match rest.iter().position(|&m| m == Mark::Absent) {
+        Some(first_absent) => {
+            let present_before = rest[..first_absent]
+                .iter()
+                .filter(|&&m| m == Mark::Present)
+                .count();
+            present_before >= present / 2
+        }
+        None => true,
     }
 }
@@ -1,31 +1,79 @@
-use crate::{feedback::Feedback, rules::Rules};
+use std::collections::HashMap;
+
+use crate::{
+    feedback::Feedback,
+    hint::{Hint, HintLevel, HintRecord},
+    quality::GuessQuality,
+    rules::{Limit, Rules},
+    scheme::Scheme,
+    solver::{self, MinimaxSolver, Solver},
+};
 use rand::Rng;
 
 pub type Code = Vec<u8>;
 
 pub struct Game {
-    secret_code: Code,
+    codemaker: Codemaker,
     rules: Rules,
     state: GameState,
 }
 
+/// Who answers the guesses.
+enum Codemaker {
+    /// The secret is chosen when the game starts.
+    Fixed(Code),
+    /// The secret is left open: every answer keeps as many codes alive as possible.
+    Adversarial { candidates: Vec<Code> },
+}
+
 impl Game {
     pub fn new(rules: Rules) -> Self {
         let secret_code = Self::generate_secret_code(&rules);
+        Self::with_codemaker(rules, Codemaker::Fixed(secret_code))
+    }
+
+    /// Creates a game with a chosen secret, e.g. one picked for its
+    /// difficulty.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the rules do not accept the secret.
+    pub fn with_secret(rules: Rules, secret_code: Code) -> Option<Self> {
+        rules
+            .accepts(&secret_code)
+            .then(|| Self::with_codemaker(rules, Codemaker::Fixed(secret_code)))
+    }
+
+    /// Creates a game against an evil codemaker that commits to no secret.
+    ///
+    /// After each guess it answers with the feedback shared by the most codes
+    /// still possible, so the secret is only settled once a single code remains.
+    /// Every code allowed by the rules is kept in memory.
+    pub fn new_adversarial(rules: Rules) -> Self {
+        let candidates = solver::all_codes(&rules);
+        Self::with_codemaker(rules, Codemaker::Adversarial { candidates })
+    }
+
+    fn with_codemaker(rules: Rules, codemaker: Codemaker) -> Self {
         Self {
-            secret_code,
+            codemaker,
             rules,
             state: GameState {
                 history: Vec::new(),
+                hints: Vec::new(),
             },
         }
     }
 
     pub fn guess(&mut self, guess: Code) -> Option<Feedback> {
-        if guess.len() != self.rules.code_len().into() {
+        if !self.rules.accepts(&guess) {
             return None;
         }
-        let feedback = Feedback::new(&self.secret_code, &guess);
+        let scheme = self.rules.scheme();
+        let feedback = match &mut self.codemaker {
+            Codemaker::Fixed(secret_code) => scheme.score(secret_code, &guess),
+            Codemaker::Adversarial { candidates } => Self::evade(scheme, candidates, &guess),
+        };
         self.state.history.push(Turn {
             guess,
             feedback: feedback.clone(),
@@ -33,25 +81,164 @@ impl Game {
         Some(feedback)
     }
 
+    /// Gives a hint of the requested level and records it in the game state.
+    ///
+    /// Hints are derived from the history only, so they never reveal more than
+    /// a perfect player could deduce.
+    ///
+    /// # Returns
+    ///
+    /// `None` when there is nothing to tell at this level, e.g. when every
+    /// symbol can still appear or has already been eliminated by a hint.
+    pub fn hint(&mut self, level: HintLevel) -> Option<Hint> {
+        let hint = match level {
+            HintLevel::Count => Hint::Remaining {
+                candidates: solver::candidates(&self.rules, &self.state.history).len(),
+            },
+            HintLevel::Eliminate => {
+                let candidates = solver::candidates(&self.rules, &self.state.history);
+                let symbol = (0..self.rules.available_symbols()).find(|symbol| {
+                    !self.state.eliminated_symbols().any(|s| s == *symbol)
+                        && candidates.iter().all(|code| !code.contains(symbol))
+                })?;
+                Hint::Absent { symbol }
+            }
+            HintLevel::Suggest => Hint::Suggestion {
+                guess: MinimaxSolver.next_guess(&self.rules, &self.state.history)?,
+            },
+        };
+        self.state
+            .hints
+            .push(HintRecord::new(self.state.history.len(), hint.clone()));
+        Some(hint)
+    }
+
+    /// Tells how `guess` would split the codes still possible, without playing
+    /// it.
+    ///
+    /// Like hints, this only uses the history, so it gives nothing away about
+    /// the secret.
+    pub fn evaluate(&self, guess: &[u8]) -> Option<GuessQuality> {
+        GuessQuality::new(&self.rules, &self.state, guess)
+    }
+
+    /// Returns `true` if the secret is left open by an evil codemaker.
+    pub fn is_adversarial(&self) -> bool {
+        matches!(self.codemaker, Codemaker::Adversarial { .. })
+    }
+
+    /// Returns the secret once it is settled: after it has been guessed, or
+    /// when an evil codemaker has a single code left.
+    pub fn resolved_secret(&self) -> Option<&Code> {
+        match &self.codemaker {
+            Codemaker::Adversarial { candidates } if candidates.len() == 1 => candidates.first(),
+            Codemaker::Adversarial { .. } => None,
+            Codemaker::Fixed(secret_code) => {
+                let last = self.state.last_feedback()?;
+                (last.exact() == self.rules.code_len()).then_some(secret_code)
+            }
+        }
+    }
+
+    /// Gives the secret away, e.g. to show it once the game is lost.
+    ///
+    /// An evil codemaker that has not settled on a secret reveals the first
+    /// code still possible.
+    pub fn reveal_secret(&self) -> &Code {
+        match &self.codemaker {
+            Codemaker::Fixed(secret_code) => secret_code,
+            Codemaker::Adversarial { candidates } => candidates
+                .first()
+                .expect("an evil codemaker always has a candidate left"),
+        }
+    }
+
+    /// Tells whether the game is won, lost or still going.
+    ///
+    /// Only attempt limits end a game here; time limits are left to the frontend.
+    pub fn status(&self) -> GameStatus {
+        let won = self
+            .state
+            .last_feedback()
+            .is_some_and(|feedback| feedback.exact() == self.rules.code_len());
+        GameStatus::new(won, self.state.history.len(), &self.rules)
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Answers `guess` with the feedback shared by the most candidates, and
+    /// keeps only those candidates.
+    ///
+    /// Ties go to the feedback with the fewest exact, then misplaced pegs.
+    /// Wordle marks left tied go to the first in the order of [`Feedback`].
+    fn evade(scheme: Scheme, candidates: &mut Vec<Code>, guess: &Code) -> Feedback {
+        let mut groups: HashMap<Feedback, Vec<Code>> = HashMap::new();
+        for candidate in candidates.drain(..) {
+            groups
+                .entry(scheme.score(&candidate, guess))
+                .or_default()
+                .push(candidate);
+        }
+        let (feedback, kept) = groups
+            .into_iter()
+            .max_by(|(a, a_codes), (b, b_codes)| {
+                a_codes
+                    .len()
+                    .cmp(&b_codes.len())
+                    .then((b.exact(), b.misplaced()).cmp(&(a.exact(), a.misplaced())))
+                    .then_with(|| b.cmp(a))
+            })
+            .expect("an evil codemaker always has a candidate left");
+        *candidates = kept;
+        feedback
+    }
+
     /// Generates the secret code for the game according to the rules.
     ///
     /// # Returns
     ///
     /// A vector of integers (`Vec<u8>`) representing the secret code.
-    fn generate_secret_code(rules: &Rules) -> Vec<u8> {
+    pub(crate) fn generate_secret_code(rules: &Rules) -> Vec<u8> {
         let mut secret_code: Vec<u8> = Vec::new();
         let mut rng = rand::rng();
         let code_len = rules.code_len();
         let available_symbols = rules.available_symbols();
-        for _ in 0..code_len {
-            secret_code.push(rng.random_range(0..available_symbols))
+        while secret_code.len() < code_len as usize {
+            let symbol = rng.random_range(0..available_symbols);
+            if rules.allows_repetition() || !secret_code.contains(&symbol) {
+                secret_code.push(symbol)
+            }
         }
         secret_code
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    InProgress,
+    Won,
+    Lost,
+}
+
+impl GameStatus {
+    pub(crate) fn new(won: bool, attempts: usize, rules: &Rules) -> Self {
+        match rules.limit() {
+            _ if won => GameStatus::Won,
+            Limit::Attempts { count } if attempts >= *count as usize => GameStatus::Lost,
+            _ => GameStatus::InProgress,
+        }
+    }
+}
+
 pub struct GameState {
     history: Vec<Turn>,
+    hints: Vec<HintRecord>,
 }
 
 impl GameState {
@@ -61,9 +248,39 @@ impl GameState {
             None => None,
         }
     }
+
+    pub fn history(&self) -> &[Turn] {
+        &self.history
+    }
+
+    pub fn hints(&self) -> &[HintRecord] {
+        &self.hints
+    }
+
+    fn eliminated_symbols(&self) -> impl Iterator<Item = u8> + '_ {
+        self.hints.iter().filter_map(|record| match record.hint() {
+            Hint::Absent { symbol } => Some(*symbol),
+            _ => None,
+        })
+    }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Turn {
     guess: Code,
     feedback: Feedback,
 }
+
+impl Turn {
+    pub fn new(guess: Code, feedback: Feedback) -> Self {
+        Turn { guess, feedback }
+    }
+
+    pub fn guess(&self) -> &Code {
+        &self.guess
+    }
+
+    pub fn feedback(&self) -> &Feedback {
+        &self.feedback
+    }
+}
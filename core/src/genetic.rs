@@ -0,0 +1,232 @@
+//! A genetic solver for code spaces too large to enumerate.
+//!
+//! Each guess evolves a population of codes towards consistency with the
+//! history, collecting the consistent codes it meets along the way. Once
+//! enough are collected, no new ones turn up, or the budget runs out, it plays
+//! the one that splits the others into the most feedback classes.
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::{
+    feedback::Feedback,
+    game::{Code, Turn},
+    rules::Rules,
+    solver::Solver,
+};
+
+/// Generations without a new consistent code after which the consistent
+/// codes found are taken to be all there are.
+const STALE_GENERATIONS: usize = 10;
+
+/// How long a [`GeneticSolver`] searches for each guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    /// Number of codes scored against the history.
+    Evaluations(usize),
+    Time(Duration),
+}
+
+/// Searches for codes consistent with the history with a genetic algorithm,
+/// without listing the codes allowed by the rules.
+///
+/// If the budget runs out before any consistent code is found, the code
+/// closest to consistent is played.
+#[derive(Debug, Clone)]
+pub struct GeneticSolver {
+    budget: Budget,
+    population: usize,
+    max_eligible: usize,
+    rng: StdRng,
+}
+
+impl GeneticSolver {
+    pub fn new(budget: Budget) -> Self {
+        GeneticSolver {
+            budget,
+            population: 150,
+            max_eligible: 60,
+            rng: StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    /// Number of codes in each generation.
+    pub fn with_population(mut self, population: usize) -> Self {
+        self.population = population.max(2);
+        self
+    }
+
+    /// Number of consistent codes after which the search stops early.
+    pub fn with_max_eligible(mut self, max_eligible: usize) -> Self {
+        self.max_eligible = max_eligible.max(1);
+        self
+    }
+
+    /// Makes the solver's guesses reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    fn random_code(&mut self, rules: &Rules) -> Code {
+        let mut code: Code = (0..rules.code_len())
+            .map(|_| self.rng.random_range(0..rules.available_symbols()))
+            .collect();
+        self.repair(rules, &mut code);
+        code
+    }
+
+    /// Replaces repeated symbols with unused ones when the rules forbid
+    /// repetition.
+    fn repair(&mut self, rules: &Rules, code: &mut Code) {
+        if rules.allows_repetition() {
+            return;
+        }
+        for position in 0..code.len() {
+            if code[..position].contains(&code[position]) {
+                let unused: Vec<u8> = (0..rules.available_symbols())
+                    .filter(|symbol| !code.contains(symbol))
+                    .collect();
+                code[position] = *unused
+                    .choose(&mut self.rng)
+                    .expect("rules have enough symbols");
+            }
+        }
+    }
+
+    /// A child of two parents, crossed over at one or two points, then
+    /// mutated.
+    fn offspring(&mut self, rules: &Rules, a: &Code, b: &Code) -> Code {
+        let len = a.len();
+        let mut child = a.clone();
+        let first = self.rng.random_range(0..len);
+        let last = if self.rng.random_bool(0.5) {
+            len
+        } else {
+            self.rng.random_range(first..=len)
+        };
+        child[first..last].copy_from_slice(&b[first..last]);
+
+        // Change a symbol, swap two positions or reverse a run of positions
+        if self.rng.random_bool(0.03) {
+            let position = self.rng.random_range(0..len);
+            child[position] = self.rng.random_range(0..rules.available_symbols());
+        }
+        if self.rng.random_bool(0.03) {
+            let (i, j) = (self.rng.random_range(0..len), self.rng.random_range(0..len));
+            child.swap(i, j);
+        }
+        if self.rng.random_bool(0.02) {
+            let i = self.rng.random_range(0..len);
+            let j = self.rng.random_range(i..len);
+            child[i..=j].reverse();
+        }
+        self.repair(rules, &mut child);
+        child
+    }
+
+    /// The better of two codes picked at random from the ranked population.
+    fn select<'a>(&mut self, ranked: &'a [(u32, Code)]) -> &'a Code {
+        let i = self.rng.random_range(0..ranked.len());
+        let j = self.rng.random_range(0..ranked.len());
+        &ranked[i.min(j)].1
+    }
+}
+
+impl Solver for GeneticSolver {
+    fn next_guess(&mut self, rules: &Rules, history: &[Turn]) -> Option<Code> {
+        let start = Instant::now();
+        let evaluate = |code: Code| (fitness(rules, &code, history), code);
+        let played = |code: &Code| history.iter().any(|turn| turn.guess() == code);
+
+        let mut ranked: Vec<(u32, Code)> = (0..self.population)
+            .map(|_| evaluate(self.random_code(rules)))
+            .collect();
+        let mut evaluations = ranked.len();
+        let mut eligible: Vec<Code> = Vec::new();
+        let mut closest: Option<(u32, Code)> = None;
+        // Generations since the last new consistent code
+        let mut stale = 0;
+        loop {
+            ranked.sort();
+            stale += 1;
+            for (fitness, code) in &ranked {
+                if played(code) {
+                    continue;
+                }
+                if *fitness == 0 && !eligible.contains(code) {
+                    eligible.push(code.clone());
+                    stale = 0;
+                }
+                if closest.as_ref().is_none_or(|(best, _)| fitness < best) {
+                    closest = Some((*fitness, code.clone()));
+                }
+            }
+            let exhausted = match self.budget {
+                Budget::Evaluations(limit) => evaluations >= limit,
+                Budget::Time(limit) => start.elapsed() >= limit,
+            };
+            let settled = !eligible.is_empty() && stale >= STALE_GENERATIONS;
+            if exhausted || settled || eligible.len() >= self.max_eligible {
+                break;
+            }
+
+            // The best tenth survives; the rest are children of the fittest,
+            // or new random codes in place of duplicates
+            let mut next: Vec<Code> = ranked[..self.population / 10]
+                .iter()
+                .map(|(_, code)| code.clone())
+                .collect();
+            while next.len() < self.population {
+                let (a, b) = (self.select(&ranked).clone(), self.select(&ranked).clone());
+                let mut child = self.offspring(rules, &a, &b);
+                if next.contains(&child) {
+                    child = self.random_code(rules);
+                }
+                next.push(child);
+            }
+            ranked = next.into_iter().map(evaluate).collect();
+            evaluations += ranked.len();
+        }
+
+        if eligible.is_empty() {
+            return closest.map(|(_, code)| code);
+        }
+        most_informative(rules, &eligible)
+    }
+}
+
+/// How far the code is from being consistent: the pegs or marks by which
+/// its feedback would differ from each turn's.
+fn fitness(rules: &Rules, code: &Code, history: &[Turn]) -> u32 {
+    history
+        .iter()
+        .map(|turn| {
+            rules
+                .scheme()
+                .score(code, turn.guess())
+                .distance(turn.feedback())
+        })
+        .sum()
+}
+
+/// The code that splits the others into the most feedback classes, taking
+/// them as a sample of the secrets still possible.
+fn most_informative(rules: &Rules, eligible: &[Code]) -> Option<Code> {
+    let mut best: Option<(usize, &Code)> = None;
+    for guess in eligible {
+        let classes = eligible
+            .iter()
+            .map(|secret| rules.scheme().score(secret, guess))
+            .collect::<HashSet<Feedback>>()
+            .len();
+        if best.is_none_or(|(most, _)| classes > most) {
+            best = Some((classes, guess));
+        }
+    }
+    best.map(|(_, guess)| guess.clone())
+}
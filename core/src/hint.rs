@@ -0,0 +1,50 @@
+use crate::game::Code;
+
+/// How much a hint gives away, from the vaguest to the most revealing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintLevel {
+    /// Tells how many codes are still consistent with the history.
+    Count,
+    /// Names one symbol that cannot appear in the secret.
+    Eliminate,
+    /// Suggests a guess to play next.
+    Suggest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    Remaining { candidates: usize },
+    Absent { symbol: u8 },
+    Suggestion { guess: Code },
+}
+
+impl Hint {
+    pub fn level(&self) -> HintLevel {
+        match self {
+            Hint::Remaining { .. } => HintLevel::Count,
+            Hint::Absent { .. } => HintLevel::Eliminate,
+            Hint::Suggestion { .. } => HintLevel::Suggest,
+        }
+    }
+}
+
+/// A hint given during the game, along with the number of guesses made before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HintRecord {
+    turn: usize,
+    hint: Hint,
+}
+
+impl HintRecord {
+    pub(crate) fn new(turn: usize, hint: Hint) -> Self {
+        HintRecord { turn, hint }
+    }
+
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn hint(&self) -> &Hint {
+        &self.hint
+    }
+}
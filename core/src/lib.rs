@@ -1,6 +1,29 @@
+pub mod analysis;
+pub mod book;
+pub mod candidates;
+pub mod constraint;
+pub mod deduction;
+pub mod enumeration;
 pub mod feedback;
 pub mod game;
+pub mod genetic;
+pub mod hint;
+pub mod multi;
+pub mod packed;
+pub mod puzzle;
+pub mod quality;
+pub mod rating;
+pub mod replay;
+pub mod report;
+pub mod reverse;
 pub mod rules;
+pub mod scheme;
+pub mod solver;
+pub mod static_game;
+pub mod strategy;
+pub mod symmetry;
+pub mod table;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
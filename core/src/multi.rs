@@ -0,0 +1,127 @@
+use crate::{
+    feedback::Feedback,
+    game::{Code, Game, GameStatus, Turn},
+    rules::Rules,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MultiGameError {
+    NoBoards,
+}
+
+/// A guess and what each board answered; solved boards no longer answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiTurn {
+    guess: Code,
+    feedbacks: Vec<Option<Feedback>>,
+}
+
+impl MultiTurn {
+    pub fn guess(&self) -> &Code {
+        &self.guess
+    }
+
+    pub fn feedbacks(&self) -> &[Option<Feedback>] {
+        &self.feedbacks
+    }
+}
+
+/// Several independent secrets under the same rules, solved with shared guesses.
+pub struct MultiGame {
+    secret_codes: Vec<Code>,
+    rules: Rules,
+    history: Vec<MultiTurn>,
+    solved_at: Vec<Option<usize>>,
+}
+
+impl MultiGame {
+    pub fn new(rules: Rules, boards: usize) -> Result<Self, MultiGameError> {
+        let secret_codes = (0..boards)
+            .map(|_| Game::generate_secret_code(&rules))
+            .collect();
+        Self::with_secrets(rules, secret_codes)
+    }
+
+    pub(crate) fn with_secrets(
+        rules: Rules,
+        secret_codes: Vec<Code>,
+    ) -> Result<Self, MultiGameError> {
+        if secret_codes.is_empty() {
+            return Err(MultiGameError::NoBoards);
+        }
+        Ok(MultiGame {
+            solved_at: vec![None; secret_codes.len()],
+            secret_codes,
+            rules,
+            history: Vec::new(),
+        })
+    }
+
+    /// Scores the guess against every unsolved board.
+    ///
+    /// # Returns
+    ///
+    /// One entry per board, `None` for boards solved by an earlier guess, or
+    /// `None` overall if the rules do not accept the guess or the game is over.
+    pub fn guess(&mut self, guess: Code) -> Option<Vec<Option<Feedback>>> {
+        if !self.rules.accepts(&guess) || self.status() != GameStatus::InProgress {
+            return None;
+        }
+        let scheme = self.rules.scheme();
+        let turn = self.history.len();
+        let feedbacks: Vec<Option<Feedback>> = self
+            .secret_codes
+            .iter()
+            .zip(&mut self.solved_at)
+            .map(|(secret_code, solved_at)| {
+                if solved_at.is_some() {
+                    return None;
+                }
+                let feedback = scheme.score(secret_code, &guess);
+                if feedback.exact() == self.rules.code_len() {
+                    *solved_at = Some(turn);
+                }
+                Some(feedback)
+            })
+            .collect();
+        self.history.push(MultiTurn {
+            guess,
+            feedbacks: feedbacks.clone(),
+        });
+        Some(feedbacks)
+    }
+
+    /// Won once every board is solved; attempts are shared by all boards.
+    pub fn status(&self) -> GameStatus {
+        let won = self.solved_at.iter().all(Option::is_some);
+        GameStatus::new(won, self.history.len(), &self.rules)
+    }
+
+    pub fn boards(&self) -> usize {
+        self.secret_codes.len()
+    }
+
+    /// Index of the turn that solved the board, if any.
+    pub fn solved_at(&self, board: usize) -> Option<usize> {
+        self.solved_at.get(board).copied().flatten()
+    }
+
+    pub fn history(&self) -> &[MultiTurn] {
+        &self.history
+    }
+
+    /// The turns as seen by one board, up to the guess that solved it.
+    pub fn board_history(&self, board: usize) -> Vec<Turn> {
+        self.history
+            .iter()
+            .filter_map(|turn| {
+                let feedback = turn.feedbacks.get(board)?.clone()?;
+                Some(Turn::new(turn.guess.clone(), feedback))
+            })
+            .collect()
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+}
@@ -0,0 +1,106 @@
+//! Codes packed into machine words, for scoring many codes quickly.
+
+use crate::{
+    feedback::Feedback,
+    game::Code,
+    rules::{CodeLen, Rules},
+};
+
+/// Nibbles of a `u64`.
+const NIBBLE_LOW: u64 = 0x7777_7777_7777_7777;
+const NIBBLE_HIGH: u64 = 0x8888_8888_8888_8888;
+/// Bytes of a `u128`.
+const BYTE_ONE: u128 = 0x0101_0101_0101_0101_0101_0101_0101_0101;
+const BYTE_HIGH: u128 = BYTE_ONE << 7;
+
+/// A code of up to 16 positions over up to 16 symbols, one nibble per
+/// position, along with how many times each symbol appears.
+///
+/// The symbol counts take one byte each rather than a nibble, so that a code
+/// using a single symbol 16 times still fits and counts can be compared
+/// lane by lane without borrowing from their neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedCode {
+    symbols: u64,
+    counts: u128,
+    len: CodeLen,
+}
+
+impl PackedCode {
+    pub const MAX_LEN: usize = 16;
+    pub const MAX_SYMBOLS: u8 = 16;
+
+    /// Packs a code.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the code is longer than [`Self::MAX_LEN`] or uses a symbol
+    /// of [`Self::MAX_SYMBOLS`] or more.
+    pub fn new(code: &[u8]) -> Option<Self> {
+        if code.len() > Self::MAX_LEN {
+            return None;
+        }
+        let mut symbols = 0;
+        let mut counts = 0;
+        for (position, &symbol) in code.iter().enumerate() {
+            if symbol >= Self::MAX_SYMBOLS {
+                return None;
+            }
+            symbols |= (symbol as u64) << (4 * position);
+            counts += 1 << (8 * symbol as u32);
+        }
+        Some(PackedCode {
+            symbols,
+            counts,
+            len: code.len() as CodeLen,
+        })
+    }
+
+    /// Returns `true` if every code allowed by the rules can be packed.
+    pub fn fits(rules: &Rules) -> bool {
+        rules.code_len() as usize <= Self::MAX_LEN && rules.available_symbols() <= Self::MAX_SYMBOLS
+    }
+
+    pub fn unpack(&self) -> Code {
+        (0..self.len)
+            .map(|position| (self.symbols >> (4 * position as u32)) as u8 & 0xF)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Counts the pegs at the right position, and all the pegs matched once
+    /// each, like [`Feedback::new`] does for unpacked codes.
+    pub fn peg_counts(&self, other: &PackedCode) -> (CodeLen, CodeLen) {
+        // A nibble of the difference is zero exactly where the symbols agree;
+        // the unused nibbles are zero in both codes.
+        let diff = self.symbols ^ other.symbols;
+        let differing = (((diff & NIBBLE_LOW) + NIBBLE_LOW) | diff) & NIBBLE_HIGH;
+        let exact = self.len - differing.count_ones() as CodeLen;
+
+        // Per symbol, the smaller of the two counts. Counts stay below 128, so
+        // the high bit of each byte of `(a | high) - b` tells whether a >= b.
+        let (a, b) = (self.counts, other.counts);
+        let a_not_less = ((a | BYTE_HIGH) - b) & BYTE_HIGH;
+        let take_b = (a_not_less >> 7) * 0xFF;
+        let smaller = (b & take_b) | (a & !take_b);
+        // The top byte of the product sums every byte, which is at most 16.
+        let matched = (smaller.wrapping_mul(BYTE_ONE) >> 120) as CodeLen;
+        (exact, matched)
+    }
+
+    /// Scores a guess with classic black and white pegs.
+    pub fn feedback(&self, guess: &PackedCode) -> Feedback {
+        let (exact, matched) = self.peg_counts(guess);
+        Feedback::Pegs {
+            exact,
+            misplaced: matched - exact,
+        }
+    }
+}
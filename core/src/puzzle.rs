@@ -0,0 +1,166 @@
+use rand::Rng;
+
+use crate::{
+    deduction::Deductions,
+    game::{Code, Turn},
+    replay::Replay,
+    rules::Rules,
+    solver,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// The guess of the turn at this index does not have the rules' code length.
+    WrongCodeLength { turn: usize },
+    /// No code satisfies the turns at these indices taken together, and
+    /// dropping any one of them removes the conflict.
+    Contradiction { conflicting: Vec<usize> },
+}
+
+/// Lists every code consistent with a set of turns, without a secret.
+///
+/// # Returns
+///
+/// The consistent codes in lexicographic order, or a minimal group of turns
+/// that cannot all hold at once: no turn can be left out of it, though a
+/// smaller group may exist elsewhere among the turns.
+pub fn solve(rules: &Rules, turns: &[Turn]) -> Result<Vec<Code>, PuzzleError> {
+    if let Some(turn) = turns
+        .iter()
+        .position(|turn| turn.guess().len() != rules.code_len() as usize)
+    {
+        return Err(PuzzleError::WrongCodeLength { turn });
+    }
+
+    let solutions = solver::candidates(rules, turns);
+    if !solutions.is_empty() {
+        return Ok(solutions);
+    }
+
+    // Drop every turn that is not needed to keep the conflict.
+    let mut conflicting: Vec<usize> = (0..turns.len()).collect();
+    let mut index = 0;
+    while index < conflicting.len() {
+        let without: Vec<Turn> = conflicting
+            .iter()
+            .filter(|&&turn| turn != conflicting[index])
+            .map(|&turn| turns[turn].clone())
+            .collect();
+        if solver::candidates(rules, &without).is_empty() {
+            conflicting.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+    Err(PuzzleError::Contradiction { conflicting })
+}
+
+/// How hard a generated puzzle should be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difficulty {
+    /// Largest number of rows the puzzle may have.
+    pub max_rows: usize,
+    /// Smallest deduction depth the puzzle must require, see [`Puzzle::depth`].
+    pub min_depth: usize,
+}
+
+/// A set of rows whose only consistent code is the secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    secret: Code,
+    turns: Vec<Turn>,
+    depth: usize,
+}
+
+impl Puzzle {
+    pub fn secret(&self) -> &Code {
+        &self.secret
+    }
+
+    pub fn turns(&self) -> &[Turn] {
+        &self.turns
+    }
+
+    /// Number of constraint-propagation rounds needed to derive the secret
+    /// from the rows, plus one when propagation alone cannot single it out.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The puzzle in the replay text format, without the secret so it can be shared.
+    pub fn replay(&self, rules: &Rules) -> Replay {
+        Replay::new(rules.clone(), self.turns.clone(), None)
+    }
+}
+
+/// Tries to build a puzzle matching the difficulty with a random secret.
+///
+/// Rows are random guesses that each rule out some codes, kept until the secret
+/// is the only consistent code and then pruned so that no row can be dropped.
+///
+/// # Returns
+///
+/// `None` if no matching puzzle was found within `attempts` secrets.
+pub fn generate<R: Rng + ?Sized>(
+    rules: &Rules,
+    difficulty: &Difficulty,
+    attempts: usize,
+    rng: &mut R,
+) -> Option<Puzzle> {
+    let codes = solver::all_codes(rules);
+    for _ in 0..attempts {
+        let secret = codes[rng.random_range(0..codes.len())].clone();
+        let puzzle = generate_for(rules, &codes, secret, rng);
+        if puzzle.turns.len() <= difficulty.max_rows && puzzle.depth >= difficulty.min_depth {
+            return Some(puzzle);
+        }
+    }
+    None
+}
+
+fn generate_for<R: Rng + ?Sized>(
+    rules: &Rules,
+    codes: &[Code],
+    secret: Code,
+    rng: &mut R,
+) -> Puzzle {
+    let mut turns: Vec<Turn> = Vec::new();
+    let mut remaining = codes.len();
+    while remaining > 1 {
+        let guess = &codes[rng.random_range(0..codes.len())];
+        if *guess == secret {
+            continue;
+        }
+        let mut with_guess = turns.clone();
+        let feedback = rules.scheme().score(&secret, guess);
+        with_guess.push(Turn::new(guess.clone(), feedback));
+        let left = solver::candidates(rules, &with_guess).len();
+        if left < remaining {
+            turns = with_guess;
+            remaining = left;
+        }
+    }
+
+    let mut index = 0;
+    while index < turns.len() {
+        let mut without = turns.clone();
+        without.remove(index);
+        if solver::candidates(rules, &without).len() == 1 {
+            turns = without;
+        } else {
+            index += 1;
+        }
+    }
+
+    let deductions = Deductions::new(rules, &turns);
+    let depth = if deductions.admitted_codes().len() == 1 {
+        deductions.rounds()
+    } else {
+        deductions.rounds() + 1
+    };
+    Puzzle {
+        secret,
+        turns,
+        depth,
+    }
+}
@@ -0,0 +1,128 @@
+//! How good a guess is before playing it: how it would split the codes still
+//! possible, by the feedback each of them would give.
+
+use crate::{
+    candidates::CandidateSet,
+    feedback::Feedback,
+    game::{Code, GameState, Turn},
+    rules::Rules,
+};
+
+/// The outcome of a proposed guess against every code still possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuessQuality {
+    guess: Code,
+    /// The codes left after each feedback, largest first.
+    partition: Vec<(Feedback, CandidateSet)>,
+    remaining: usize,
+    could_be_secret: bool,
+}
+
+impl GuessQuality {
+    /// Evaluates `guess` against the codes consistent with the game so far.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the rules do not accept the guess, or their codes cannot be
+    /// held in a [`CandidateSet`].
+    pub fn new(rules: &Rules, state: &GameState, guess: &[u8]) -> Option<Self> {
+        Self::from_history(rules, state.history(), guess)
+    }
+
+    /// Evaluates `guess` against the codes consistent with `history`.
+    pub fn from_history(rules: &Rules, history: &[Turn], guess: &[u8]) -> Option<Self> {
+        let candidates = CandidateSet::from_history(rules, history)?;
+        Self::among(rules, &candidates, guess)
+    }
+
+    /// Evaluates `guess` against the given codes, taken as the secrets still
+    /// possible.
+    pub fn among(rules: &Rules, candidates: &CandidateSet, guess: &[u8]) -> Option<Self> {
+        if !rules.accepts(guess) {
+            return None;
+        }
+        let guess = guess.to_vec();
+        let mut partition: Vec<(Feedback, CandidateSet)> = Vec::new();
+        for code in candidates.iter() {
+            let feedback = rules.scheme().score(&code, &guess);
+            match partition.iter_mut().find(|(f, _)| *f == feedback) {
+                Some((_, codes)) => {
+                    codes.insert(&code);
+                }
+                None => {
+                    let mut codes = CandidateSet::empty(rules)?;
+                    codes.insert(&code);
+                    partition.push((feedback, codes));
+                }
+            }
+        }
+        // Stable, so equal classes stay in the order of their first code
+        partition.sort_by_key(|(_, codes)| std::cmp::Reverse(codes.len()));
+        Some(GuessQuality {
+            could_be_secret: candidates.contains(&guess),
+            remaining: candidates.len(),
+            guess,
+            partition,
+        })
+    }
+
+    pub fn guess(&self) -> &Code {
+        &self.guess
+    }
+
+    /// The codes that would be left after each feedback the guess can get,
+    /// the largest group first.
+    pub fn partition(&self) -> &[(Feedback, CandidateSet)] {
+        &self.partition
+    }
+
+    /// The codes left after `feedback`, if any code would give it.
+    pub fn after(&self, feedback: &Feedback) -> Option<&CandidateSet> {
+        self.partition
+            .iter()
+            .find(|(f, _)| f == feedback)
+            .map(|(_, codes)| codes)
+    }
+
+    /// Number of codes possible before the guess.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Most codes the guess can leave.
+    pub fn worst_case(&self) -> usize {
+        self.partition.first().map_or(0, |(_, codes)| codes.len())
+    }
+
+    /// Number of codes the guess leaves on average, each possible code being
+    /// equally likely to be the secret.
+    pub fn expected_size(&self) -> f64 {
+        if self.remaining == 0 {
+            return 0.0;
+        }
+        let squares: usize = self
+            .partition
+            .iter()
+            .map(|(_, codes)| codes.len() * codes.len())
+            .sum();
+        squares as f64 / self.remaining as f64
+    }
+
+    /// Information the feedback is expected to give, in bits.
+    pub fn entropy(&self) -> f64 {
+        let total = self.remaining as f64;
+        self.partition
+            .iter()
+            .map(|(_, codes)| {
+                let p = codes.len() as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    /// Returns `true` if the guess is itself one of the codes still possible,
+    /// so it can win the game.
+    pub fn could_be_secret(&self) -> bool {
+        self.could_be_secret
+    }
+}
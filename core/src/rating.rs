@@ -0,0 +1,176 @@
+//! Difficulty ratings for rules and for secrets, so that secrets of a given
+//! difficulty can be picked.
+//!
+//! Rules are rated by the number of codes they allow and the guesses a
+//! reference strategy needs, and each secret by the guesses the same strategy
+//! needs for it. The strategy can be a solver's, or an optimal one from
+//! [`analysis`](crate::analysis).
+
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    enumeration::CodeSpace,
+    game::{Code, Turn},
+    rules::Rules,
+    solver::Solver,
+    strategy::Strategy,
+};
+
+/// How hard some rules are under a reference strategy.
+#[derive(Debug, Clone)]
+pub struct RulesRating {
+    rules: Rules,
+    space_size: u64,
+    strategy: Strategy,
+    histogram: Vec<usize>,
+}
+
+impl RulesRating {
+    /// Rates the rules by the guesses the solver needs for every secret.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the solver gives up or repeats a guess before some secret is
+    /// found, or the codes allowed by the rules cannot be counted.
+    pub fn new<S: Solver + ?Sized>(solver: &mut S, rules: &Rules) -> Option<Self> {
+        Self::from_strategy(rules, Strategy::from_solver(solver, rules)?)
+    }
+
+    /// Rates the rules by the guesses the strategy needs, e.g. the optimal
+    /// strategy of an [`Analysis`](crate::analysis::Analysis).
+    pub fn from_strategy(rules: &Rules, strategy: Strategy) -> Option<Self> {
+        Some(RulesRating {
+            rules: rules.clone(),
+            space_size: CodeSpace::new(rules)?.size(),
+            histogram: strategy.histogram(),
+            strategy,
+        })
+    }
+
+    /// Number of codes the rules allow.
+    pub fn space_size(&self) -> u64 {
+        self.space_size
+    }
+
+    /// Information needed to single out the secret, in bits.
+    pub fn bits(&self) -> f64 {
+        (self.space_size as f64).log2()
+    }
+
+    /// Guesses the strategy needs on average.
+    pub fn expected_guesses(&self) -> f64 {
+        self.strategy.total_guesses() as f64 / self.strategy.secrets() as f64
+    }
+
+    /// Most guesses the strategy needs for a secret.
+    pub fn worst_case(&self) -> usize {
+        self.histogram.len()
+    }
+
+    /// How many secrets are solved with each number of guesses, starting
+    /// with one guess.
+    pub fn histogram(&self) -> &[usize] {
+        &self.histogram
+    }
+
+    pub fn strategy(&self) -> &Strategy {
+        &self.strategy
+    }
+
+    /// Rates a secret by the guesses the strategy needs for it.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the strategy does not find the secret.
+    pub fn secret(&self, secret: &Code) -> Option<SecretRating> {
+        let mut node = &self.strategy;
+        let mut guesses = 1;
+        while node.guess() != secret {
+            let feedback = self.rules.scheme().score(secret, node.guess());
+            node = node.branch(&feedback)?;
+            guesses += 1;
+        }
+        if !node.solves() {
+            return None;
+        }
+        Some(SecretRating {
+            guesses,
+            easier: self.histogram[..guesses - 1].iter().sum(),
+            secrets: self.histogram.iter().sum(),
+        })
+    }
+
+    /// The secrets the strategy finds with exactly `guesses` guesses, in the
+    /// order of the tree.
+    pub fn secrets_needing(&self, guesses: usize) -> Vec<Code> {
+        let mut secrets = Vec::new();
+        collect(&self.strategy, guesses, &mut secrets);
+        secrets
+    }
+
+    /// A random secret the strategy finds with exactly `guesses` guesses, as
+    /// daily challenges and campaigns need.
+    pub fn pick_secret<R: Rng + ?Sized>(&self, guesses: usize, rng: &mut R) -> Option<Code> {
+        self.secrets_needing(guesses).choose(rng).cloned()
+    }
+}
+
+/// How hard a secret is under a reference strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecretRating {
+    guesses: usize,
+    /// Secrets the strategy finds with fewer guesses.
+    easier: usize,
+    secrets: usize,
+}
+
+impl SecretRating {
+    /// Guesses the strategy needs for the secret, counting the winning one.
+    pub fn guesses(&self) -> usize {
+        self.guesses
+    }
+
+    /// Share of the secrets that take fewer guesses, from 0 for the easiest
+    /// secrets to nearly 1 for the hardest.
+    pub fn percentile(&self) -> f64 {
+        self.easier as f64 / self.secrets as f64
+    }
+}
+
+/// Plays the solver against the secret, for rules too large to rate every
+/// secret.
+///
+/// # Returns
+///
+/// The guesses needed, counting the winning one, or `None` if the solver
+/// gives up or has not found the secret after `max_guesses`.
+pub fn guesses_needed<S: Solver + ?Sized>(
+    solver: &mut S,
+    rules: &Rules,
+    secret: &Code,
+    max_guesses: usize,
+) -> Option<usize> {
+    let mut history: Vec<Turn> = Vec::new();
+    while history.len() < max_guesses {
+        let guess = solver.next_guess(rules, &history)?;
+        if guess == *secret {
+            return Some(history.len() + 1);
+        }
+        let feedback = rules.scheme().score(secret, &guess);
+        history.push(Turn::new(guess, feedback));
+    }
+    None
+}
+
+fn collect(node: &Strategy, guesses: usize, secrets: &mut Vec<Code>) {
+    match guesses {
+        0 => {}
+        1 if node.solves() => secrets.push(node.guess().clone()),
+        1 => {}
+        _ => {
+            for (_, branch) in node.branches() {
+                collect(branch, guesses - 1, secrets);
+            }
+        }
+    }
+}
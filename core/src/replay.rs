@@ -0,0 +1,283 @@
+//! Plain-text record of a game, used to share games and puzzles.
+//!
+//! ```text
+//! # Lines starting with '#' are comments
+//! rules 4 6 attempts 10
+//! turn 0 0 1 1 = 1 0
+//! turn 2 3 4 5 = 0 2
+//! secret 1 2 3 4
+//! ```
+//!
+//! The `rules` line gives the code length, the number of symbols and the limit
+//! (`attempts N`, `time SECONDS` or `unlimited`), optionally followed by the
+//! feedback scheme (`scheme classic`, `scheme exact`, `scheme wordle` or
+//! `scheme bulls-and-cows`). Each `turn` line lists the guessed symbols, then
+//! the feedback after `=`: the exact and misplaced counts, the exact count
+//! alone, or one letter per position for Wordle marks (`C` correct, `P`
+//! present, `A` absent). The `secret` line is optional.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    feedback::{Feedback, FeedbackError, Mark},
+    game::{Code, Turn},
+    rules::{Limit, Rules, RulesError},
+    scheme::Scheme,
+};
+
+#[derive(Debug)]
+pub enum ReplayError {
+    MissingRules,
+    InvalidRules(RulesError),
+    Malformed { line: usize },
+    WrongCodeLength { line: usize },
+    InvalidFeedback { line: usize, error: FeedbackError },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    rules: Rules,
+    turns: Vec<Turn>,
+    secret: Option<Code>,
+}
+
+impl Replay {
+    pub fn new(rules: Rules, turns: Vec<Turn>, secret: Option<Code>) -> Self {
+        Replay {
+            rules,
+            turns,
+            secret,
+        }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn turns(&self) -> &[Turn] {
+        &self.turns
+    }
+
+    pub fn secret(&self) -> Option<&Code> {
+        self.secret.as_ref()
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rules {} {} ",
+            self.rules.code_len(),
+            self.rules.available_symbols()
+        )?;
+        match self.rules.limit() {
+            Limit::Attempts { count } => write!(f, "attempts {}", count)?,
+            Limit::Time { seconds } => write!(f, "time {}", seconds)?,
+            Limit::NoLimitation => write!(f, "unlimited")?,
+        }
+        match self.rules.scheme() {
+            Scheme::Classic => writeln!(f)?,
+            scheme => writeln!(f, " scheme {}", scheme_name(scheme))?,
+        }
+        for turn in &self.turns {
+            writeln!(
+                f,
+                "turn {} = {}",
+                format_code(turn.guess()),
+                format_feedback(turn.feedback())
+            )?;
+        }
+        if let Some(secret) = &self.secret {
+            writeln!(f, "secret {}", format_code(secret))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = ReplayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules: Option<Rules> = None;
+        let mut turns = Vec::new();
+        let mut secret = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let malformed = ReplayError::Malformed { line: line_number };
+            let (keyword, rest) = line.split_once(' ').ok_or(malformed)?;
+            match (keyword, &rules) {
+                ("rules", None) => {
+                    rules = Some(parse_rules(rest, line_number)?);
+                }
+                ("turn", Some(rules)) => {
+                    let (guess, feedback) = rest
+                        .split_once('=')
+                        .ok_or(ReplayError::Malformed { line: line_number })?;
+                    let guess = parse_code(guess, rules, line_number)?;
+                    let feedback = parse_feedback(feedback, rules, line_number)?;
+                    turns.push(Turn::new(guess, feedback));
+                }
+                ("secret", Some(rules)) if secret.is_none() => {
+                    secret = Some(parse_code(rest, rules, line_number)?);
+                }
+                ("turn" | "secret", None) => return Err(ReplayError::MissingRules),
+                _ => return Err(ReplayError::Malformed { line: line_number }),
+            }
+        }
+
+        let rules = rules.ok_or(ReplayError::MissingRules)?;
+        Ok(Replay::new(rules, turns, secret))
+    }
+}
+
+/// Writes a code as space-separated symbols.
+pub fn format_code(code: &Code) -> String {
+    code.iter()
+        .map(|symbol| symbol.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Writes a feedback the way `turn` lines give it.
+pub fn format_feedback(feedback: &Feedback) -> String {
+    match feedback {
+        Feedback::Pegs { exact, misplaced } => format!("{} {}", exact, misplaced),
+        Feedback::Exact { exact } => exact.to_string(),
+        Feedback::Marks(marks) => marks
+            .iter()
+            .map(|mark| match mark {
+                Mark::Correct => 'C',
+                Mark::Present => 'P',
+                Mark::Absent => 'A',
+            })
+            .collect(),
+    }
+}
+
+/// Writes a feedback as a JSON object: the exact and misplaced counts, the
+/// exact count alone, or the marks as in `turn` lines.
+pub(crate) fn feedback_json(feedback: &Feedback) -> String {
+    match feedback {
+        Feedback::Pegs { exact, misplaced } => {
+            format!("{{\"exact\":{},\"misplaced\":{}}}", exact, misplaced)
+        }
+        Feedback::Exact { exact } => format!("{{\"exact\":{}}}", exact),
+        Feedback::Marks(_) => format!("{{\"marks\":\"{}\"}}", format_feedback(feedback)),
+    }
+}
+
+pub(crate) fn scheme_name(scheme: Scheme) -> &'static str {
+    match scheme {
+        Scheme::Classic => "classic",
+        Scheme::ExactOnly => "exact",
+        Scheme::Wordle => "wordle",
+        Scheme::BullsAndCows => "bulls-and-cows",
+    }
+}
+
+pub(crate) fn parse_scheme(name: &str) -> Option<Scheme> {
+    [
+        Scheme::Classic,
+        Scheme::ExactOnly,
+        Scheme::Wordle,
+        Scheme::BullsAndCows,
+    ]
+    .into_iter()
+    .find(|&scheme| scheme_name(scheme) == name)
+}
+
+fn parse_rules(s: &str, line: usize) -> Result<Rules, ReplayError> {
+    let mut words: Vec<&str> = s.split_whitespace().collect();
+    let malformed = ReplayError::Malformed { line };
+    let scheme = match words[..] {
+        [.., "scheme", name] => {
+            let scheme = parse_scheme(name).ok_or(ReplayError::Malformed { line })?;
+            words.truncate(words.len() - 2);
+            scheme
+        }
+        _ => Scheme::Classic,
+    };
+    let (code_len, symbols, limit) = match words[..] {
+        [code_len, symbols, "unlimited"] => (code_len, symbols, Limit::NoLimitation),
+        [code_len, symbols, "attempts", count] => (
+            code_len,
+            symbols,
+            Limit::Attempts {
+                count: count.parse().map_err(|_| malformed)?,
+            },
+        ),
+        [code_len, symbols, "time", seconds] => (
+            code_len,
+            symbols,
+            Limit::Time {
+                seconds: seconds.parse().map_err(|_| malformed)?,
+            },
+        ),
+        _ => return Err(malformed),
+    };
+    let code_len = code_len
+        .parse()
+        .map_err(|_| ReplayError::Malformed { line })?;
+    let symbols = symbols
+        .parse()
+        .map_err(|_| ReplayError::Malformed { line })?;
+    Rules::new(code_len, limit, symbols)
+        .and_then(|rules| rules.with_scheme(scheme))
+        .map_err(ReplayError::InvalidRules)
+}
+
+pub(crate) fn parse_feedback(s: &str, rules: &Rules, line: usize) -> Result<Feedback, ReplayError> {
+    let feedback = match rules.scheme() {
+        Scheme::Classic | Scheme::BullsAndCows => {
+            let counts = parse_numbers::<u8>(s, line)?;
+            let [exact, misplaced] = counts[..] else {
+                return Err(ReplayError::Malformed { line });
+            };
+            Feedback::from_counts(exact, misplaced, rules)
+        }
+        Scheme::ExactOnly => {
+            let counts = parse_numbers::<u8>(s, line)?;
+            let [exact] = counts[..] else {
+                return Err(ReplayError::Malformed { line });
+            };
+            Feedback::from_exact(exact, rules)
+        }
+        Scheme::Wordle => {
+            let marks = s
+                .trim()
+                .chars()
+                .map(|letter| match letter {
+                    'C' => Ok(Mark::Correct),
+                    'P' => Ok(Mark::Present),
+                    'A' => Ok(Mark::Absent),
+                    _ => Err(ReplayError::Malformed { line }),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Feedback::from_marks(marks, rules)
+        }
+    };
+    feedback.map_err(|error| ReplayError::InvalidFeedback { line, error })
+}
+
+pub(crate) fn parse_code(s: &str, rules: &Rules, line: usize) -> Result<Code, ReplayError> {
+    let code = parse_numbers::<u8>(s, line)?;
+    if code.len() != rules.code_len() as usize {
+        return Err(ReplayError::WrongCodeLength { line });
+    }
+    if !rules.accepts(&code) {
+        return Err(ReplayError::Malformed { line });
+    }
+    Ok(code)
+}
+
+fn parse_numbers<T: FromStr>(s: &str, line: usize) -> Result<Vec<T>, ReplayError> {
+    s.split_whitespace()
+        .map(|word| word.parse().map_err(|_| ReplayError::Malformed { line }))
+        .collect()
+}
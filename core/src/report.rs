@@ -0,0 +1,236 @@
+//! Post-game analysis: each turn of a finished game next to the move a
+//! solver would have made in the same position.
+//!
+//! A turn is a blunder when its guess can leave more codes than the solver's
+//! move does, not counting the code it wins with.
+
+use std::fmt::{self, Write};
+
+use crate::{
+    candidates::CandidateSet,
+    feedback::Feedback,
+    game::{Code, Turn},
+    quality::GuessQuality,
+    replay::{self, Replay},
+    rules::Rules,
+    solver::{MinimaxSolver, Solver},
+};
+
+/// One turn of the game, measured against the solver's move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnReport {
+    guess: Code,
+    feedback: Feedback,
+    before: usize,
+    left: usize,
+    bits: f64,
+    entropy: f64,
+    worst_case: usize,
+    best: Code,
+    best_entropy: f64,
+    best_worst_case: usize,
+}
+
+impl TurnReport {
+    pub fn guess(&self) -> &Code {
+        &self.guess
+    }
+
+    pub fn feedback(&self) -> &Feedback {
+        &self.feedback
+    }
+
+    /// Number of codes possible before the turn.
+    pub fn before(&self) -> usize {
+        self.before
+    }
+
+    /// Number of codes still possible after the turn; none once the game is
+    /// won.
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    /// Information the feedback actually gave, in bits.
+    pub fn bits(&self) -> f64 {
+        self.bits
+    }
+
+    /// Information the guess was expected to give, in bits.
+    pub fn entropy(&self) -> f64 {
+        self.entropy
+    }
+
+    /// Most codes the guess could have left.
+    pub fn worst_case(&self) -> usize {
+        self.worst_case
+    }
+
+    /// The solver's move in the same position.
+    pub fn best(&self) -> &Code {
+        &self.best
+    }
+
+    pub fn best_entropy(&self) -> f64 {
+        self.best_entropy
+    }
+
+    pub fn best_worst_case(&self) -> usize {
+        self.best_worst_case
+    }
+
+    /// Returns `true` if the guess could leave more codes than the solver's
+    /// move.
+    pub fn is_blunder(&self) -> bool {
+        self.worst_case > self.best_worst_case
+    }
+}
+
+/// Every turn of a game, measured against a solver's moves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameReport {
+    turns: Vec<TurnReport>,
+    won: bool,
+}
+
+impl GameReport {
+    /// Measures each turn of `history` against the guess `solver` makes after
+    /// the turns before it.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the turns contradict each other, the solver gives up, or the
+    /// codes of the rules cannot be held in a [`CandidateSet`].
+    pub fn new<S: Solver + ?Sized>(
+        solver: &mut S,
+        rules: &Rules,
+        history: &[Turn],
+    ) -> Option<Self> {
+        let mut candidates = CandidateSet::full(rules)?;
+        let mut turns = Vec::with_capacity(history.len());
+        let mut won = false;
+        for (index, turn) in history.iter().enumerate() {
+            let best = solver.next_guess(rules, &history[..index])?;
+            let played = GuessQuality::among(rules, &candidates, turn.guess())?;
+            let suggested = GuessQuality::among(rules, &candidates, &best)?;
+            let after = played.after(turn.feedback())?.clone();
+            won = turn.feedback().exact() == rules.code_len();
+            turns.push(TurnReport {
+                guess: turn.guess().clone(),
+                feedback: turn.feedback().clone(),
+                before: candidates.len(),
+                left: if won { 0 } else { after.len() },
+                bits: (candidates.len() as f64 / after.len() as f64).log2(),
+                entropy: played.entropy(),
+                worst_case: worst_left(rules, &played),
+                best,
+                best_entropy: suggested.entropy(),
+                best_worst_case: worst_left(rules, &suggested),
+            });
+            candidates = after;
+        }
+        Some(GameReport { turns, won })
+    }
+
+    /// Measures the turns of a replay against [`MinimaxSolver`].
+    pub fn from_replay(replay: &Replay) -> Option<Self> {
+        Self::new(&mut MinimaxSolver, replay.rules(), replay.turns())
+    }
+
+    pub fn turns(&self) -> &[TurnReport] {
+        &self.turns
+    }
+
+    /// Returns `true` if the last turn found the secret.
+    pub fn is_won(&self) -> bool {
+        self.won
+    }
+
+    pub fn blunders(&self) -> impl Iterator<Item = &TurnReport> {
+        self.turns.iter().filter(|turn| turn.is_blunder())
+    }
+
+    pub fn to_json(&self) -> String {
+        let code_json = |code: &Code| {
+            let symbols: Vec<String> = code.iter().map(|s| s.to_string()).collect();
+            format!("[{}]", symbols.join(","))
+        };
+        let mut json = String::from("{\"turns\":[");
+        for (index, turn) in self.turns.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{{\"guess\":{},\"feedback\":{},\"before\":{},\"left\":{},\"bits\":{:.3},\
+                 \"entropy\":{:.3},\"worst_case\":{},\"best\":{},\"best_entropy\":{:.3},\
+                 \"best_worst_case\":{},\"blunder\":{}}}",
+                code_json(&turn.guess),
+                replay::feedback_json(&turn.feedback),
+                turn.before,
+                turn.left,
+                turn.bits,
+                turn.entropy,
+                turn.worst_case,
+                code_json(&turn.best),
+                turn.best_entropy,
+                turn.best_worst_case,
+                turn.is_blunder()
+            );
+        }
+        let _ = write!(
+            json,
+            "],\"won\":{},\"blunders\":{}}}",
+            self.won,
+            self.blunders().count()
+        );
+        json
+    }
+}
+
+impl fmt::Display for GameReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, turn) in self.turns.iter().enumerate() {
+            writeln!(
+                f,
+                "{}. {} = {}: {} -> {} codes, {:.2} bits (expected {:.2})",
+                index + 1,
+                replay::format_code(&turn.guess),
+                replay::format_feedback(&turn.feedback),
+                turn.before,
+                turn.left,
+                turn.bits,
+                turn.entropy
+            )?;
+            let mark = if turn.is_blunder() { "  blunder" } else { "" };
+            writeln!(
+                f,
+                "   best {}: expected {:.2} bits, at most {} left (played: {}){}",
+                replay::format_code(&turn.best),
+                turn.best_entropy,
+                turn.best_worst_case,
+                turn.worst_case,
+                mark
+            )?;
+        }
+        let outcome = if self.won { "won" } else { "not won" };
+        writeln!(
+            f,
+            "{} in {} turn(s), {} blunder(s)",
+            outcome,
+            self.turns.len(),
+            self.blunders().count()
+        )
+    }
+}
+
+/// Most codes the guess can leave, not counting the one it wins with.
+fn worst_left(rules: &Rules, quality: &GuessQuality) -> usize {
+    quality
+        .partition()
+        .iter()
+        .filter(|(feedback, _)| feedback.exact() != rules.code_len())
+        .map(|(_, codes)| codes.len())
+        .max()
+        .unwrap_or(0)
+}
@@ -0,0 +1,163 @@
+use crate::{
+    feedback::{Feedback, FeedbackError},
+    game::{Code, Turn},
+    rules::Rules,
+    solver::{self, Solver},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReverseError {
+    /// Feedback was given while no guess was waiting for it.
+    NoPendingGuess,
+    InvalidFeedback(FeedbackError),
+    NoSuchTurn,
+}
+
+/// A turn whose feedback was probably mistyped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suspect {
+    turn: usize,
+    entered: Feedback,
+    likely: Feedback,
+}
+
+impl Suspect {
+    /// Index of the suspected turn in the history.
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn entered(&self) -> &Feedback {
+        &self.entered
+    }
+
+    /// The closest feedback that would make the history consistent again.
+    pub fn likely(&self) -> &Feedback {
+        &self.likely
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    Solved {
+        secret: Code,
+    },
+    Playing,
+    /// No code matches every feedback entered so far.
+    Inconsistent {
+        suspect: Option<Suspect>,
+    },
+}
+
+/// A game where the human keeps the secret and the solver guesses it.
+pub struct ReverseGame<S: Solver> {
+    rules: Rules,
+    solver: S,
+    history: Vec<Turn>,
+    pending: Option<Code>,
+}
+
+impl<S: Solver> ReverseGame<S> {
+    pub fn new(rules: Rules, solver: S) -> Self {
+        ReverseGame {
+            rules,
+            solver,
+            history: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Returns the guess waiting for feedback, asking the solver for one if needed.
+    ///
+    /// # Returns
+    ///
+    /// `None` when the feedback entered so far is inconsistent.
+    pub fn next_guess(&mut self) -> Option<&Code> {
+        if self.pending.is_none() {
+            self.pending = self.solver.next_guess(&self.rules, &self.history);
+        }
+        self.pending.as_ref()
+    }
+
+    /// Records the human's feedback for the pending guess.
+    pub fn respond(&mut self, feedback: Feedback) -> Result<Progress, ReverseError> {
+        feedback
+            .validate(&self.rules)
+            .map_err(ReverseError::InvalidFeedback)?;
+        let guess = self.pending.take().ok_or(ReverseError::NoPendingGuess)?;
+        self.history.push(Turn::new(guess, feedback));
+        Ok(self.progress())
+    }
+
+    /// Replaces the feedback entered for an earlier turn.
+    pub fn correct(&mut self, turn: usize, feedback: Feedback) -> Result<Progress, ReverseError> {
+        feedback
+            .validate(&self.rules)
+            .map_err(ReverseError::InvalidFeedback)?;
+        let guess = self
+            .history
+            .get(turn)
+            .ok_or(ReverseError::NoSuchTurn)?
+            .guess()
+            .clone();
+        self.history[turn] = Turn::new(guess, feedback);
+        // The pending guess was chosen from the wrong feedback
+        self.pending = None;
+        Ok(self.progress())
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn history(&self) -> &[Turn] {
+        &self.history
+    }
+
+    fn progress(&self) -> Progress {
+        if let Some(last) = self.history.last()
+            && last.feedback().exact() == self.rules.code_len()
+        {
+            return Progress::Solved {
+                secret: last.guess().clone(),
+            };
+        }
+        if solver::candidates(&self.rules, &self.history).is_empty() {
+            return Progress::Inconsistent {
+                suspect: self.suspect(),
+            };
+        }
+        Progress::Playing
+    }
+
+    /// Finds the turn whose feedback is closest to one that would make the
+    /// history consistent.
+    ///
+    /// Ties go to the earliest turn: later guesses are chosen from earlier
+    /// answers, so a wrong early answer is what usually leads to a dead end.
+    fn suspect(&self) -> Option<Suspect> {
+        let scheme = self.rules.scheme();
+        let mut best: Option<(u32, Suspect)> = None;
+        for (index, turn) in self.history.iter().enumerate() {
+            let mut others = self.history.clone();
+            others.remove(index);
+            let closest = solver::candidates(&self.rules, &others)
+                .iter()
+                .map(|candidate| scheme.score(candidate, turn.guess()))
+                .min_by_key(|likely| turn.feedback().distance(likely));
+            let Some(likely) = closest else {
+                continue;
+            };
+            let distance = turn.feedback().distance(&likely);
+            if best.as_ref().is_none_or(|(d, _)| distance < *d) {
+                let suspect = Suspect {
+                    turn: index,
+                    entered: turn.feedback().clone(),
+                    likely,
+                };
+                best = Some((distance, suspect));
+            }
+        }
+        best.map(|(_, suspect)| suspect)
+    }
+}
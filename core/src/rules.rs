@@ -1,24 +1,30 @@
+use crate::scheme::Scheme;
+
 #[derive(Debug)]
 pub enum RulesError {
     InvalidCodeLen,
     InvalidLimit,
     InvalidAvailableSymbols,
+    /// The scheme needs codes without repeated symbols, but there are fewer
+    /// symbols than positions.
+    NotEnoughSymbols,
 }
 
 pub type CodeLen = u8;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Limit {
     Attempts { count: u16 },
     Time { seconds: u32 },
     NoLimitation,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Rules {
     code_len: CodeLen,
     limit: Limit,
     available_symbols: u8,
+    scheme: Scheme,
 }
 
 impl Rules {
@@ -32,8 +38,8 @@ impl Rules {
         }
 
         match limit {
-            Limit::Attempts { count } if count == 0 => return Err(RulesError::InvalidLimit),
-            Limit::Time { seconds } if seconds == 0 => return Err(RulesError::InvalidLimit),
+            Limit::Attempts { count: 0 } => return Err(RulesError::InvalidLimit),
+            Limit::Time { seconds: 0 } => return Err(RulesError::InvalidLimit),
             _ => {}
         }
 
@@ -41,9 +47,19 @@ impl Rules {
             code_len,
             limit,
             available_symbols,
+            scheme: Scheme::Classic,
         })
     }
 
+    /// Replaces the classic scoring with another feedback scheme.
+    pub fn with_scheme(mut self, scheme: Scheme) -> Result<Self, RulesError> {
+        if !scheme.allows_repetition() && self.available_symbols < self.code_len {
+            return Err(RulesError::NotEnoughSymbols);
+        }
+        self.scheme = scheme;
+        Ok(self)
+    }
+
     pub fn code_len(&self) -> CodeLen {
         self.code_len
     }
@@ -55,4 +71,25 @@ impl Rules {
     pub fn available_symbols(&self) -> u8 {
         self.available_symbols
     }
+
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// Returns `false` if codes may not use a symbol more than once.
+    pub fn allows_repetition(&self) -> bool {
+        self.scheme.allows_repetition()
+    }
+
+    /// Returns `true` if the code has the right length, uses only the
+    /// available symbols, and repeats none when repetition is not allowed.
+    pub fn accepts(&self, code: &[u8]) -> bool {
+        code.len() == self.code_len as usize
+            && code.iter().all(|&symbol| symbol < self.available_symbols)
+            && (self.allows_repetition()
+                || code
+                    .iter()
+                    .enumerate()
+                    .all(|(i, symbol)| !code[..i].contains(symbol)))
+    }
 }
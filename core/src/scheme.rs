@@ -0,0 +1,120 @@
+//! The ways a guess can be scored against the secret.
+
+use crate::{
+    feedback::{self, Feedback, Mark},
+    game::Code,
+    rules::{CodeLen, Rules},
+};
+
+/// The scheme used by a game, chosen through [`Rules::with_scheme`].
+///
+/// The set of schemes is closed: deductions, the constraint search, packed
+/// scoring, the notation formats and opening books all depend on which
+/// scheme is played, so a new scheme is added as a variant and handled in
+/// each of them rather than plugged in from outside.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    /// Black pegs for right symbols at the right position, white pegs for
+    /// right symbols at the wrong position.
+    #[default]
+    Classic,
+    /// Black pegs only.
+    ExactOnly,
+    /// One mark per position: correct, present elsewhere, or absent. A
+    /// repeated guess symbol is marked present only as many times as the
+    /// secret has unmatched copies of it, from left to right.
+    Wordle,
+    /// Classic pegs, called bulls and cows, on codes without repeated symbols.
+    BullsAndCows,
+}
+
+impl Scheme {
+    /// Returns `false` if codes may not use a symbol more than once.
+    pub fn allows_repetition(&self) -> bool {
+        *self != Scheme::BullsAndCows
+    }
+
+    pub fn score(&self, secret: &Code, guess: &Code) -> Feedback {
+        match self {
+            Scheme::Classic | Scheme::BullsAndCows => Feedback::new(secret, guess),
+            Scheme::ExactOnly => {
+                let (exact, _) = feedback::peg_counts(secret, guess);
+                Feedback::Exact { exact }
+            }
+            Scheme::Wordle => wordle_marks(secret, guess),
+        }
+    }
+
+    /// Lists every feedback the scheme can give under the rules. Wordle
+    /// gives up to `3^code_len` combinations of marks.
+    pub fn all_possible(&self, rules: &Rules) -> Vec<Feedback> {
+        match self {
+            Scheme::Classic | Scheme::BullsAndCows => all_pegs(rules),
+            Scheme::ExactOnly => (0..=rules.code_len())
+                .map(|exact| Feedback::Exact { exact })
+                .filter(|feedback| feedback.is_valid_for(rules))
+                .collect(),
+            Scheme::Wordle => all_marks(rules),
+        }
+    }
+}
+
+fn wordle_marks(secret: &Code, guess: &Code) -> Feedback {
+    let mut marks: Vec<Mark> = secret
+        .iter()
+        .zip(guess)
+        .map(|(s, g)| if s == g { Mark::Correct } else { Mark::Absent })
+        .collect();
+    let mut unmatched: Vec<u8> = secret
+        .iter()
+        .zip(&marks)
+        .filter(|&(_, &mark)| mark != Mark::Correct)
+        .map(|(&symbol, _)| symbol)
+        .collect();
+    for (mark, symbol) in marks.iter_mut().zip(guess) {
+        if *mark == Mark::Correct {
+            continue;
+        }
+        if let Some(index) = unmatched.iter().position(|s| s == symbol) {
+            unmatched.swap_remove(index);
+            *mark = Mark::Present;
+        }
+    }
+    Feedback::Marks(marks)
+}
+
+/// Lists every reachable combination of marks.
+fn all_marks(rules: &Rules) -> Vec<Feedback> {
+    const MARKS: [Mark; 3] = [Mark::Correct, Mark::Present, Mark::Absent];
+    let code_len = rules.code_len() as usize;
+    let mut all = Vec::new();
+    let mut digits = vec![0; code_len];
+    loop {
+        let feedback = Feedback::Marks(digits.iter().map(|&d| MARKS[d]).collect());
+        if feedback.is_valid_for(rules) {
+            all.push(feedback);
+        }
+        let mut position = code_len;
+        loop {
+            if position == 0 {
+                return all;
+            }
+            position -= 1;
+            digits[position] += 1;
+            if digits[position] < MARKS.len() {
+                break;
+            }
+            digits[position] = 0;
+        }
+    }
+}
+
+/// Lists the valid peg feedback for the rules, by exact then misplaced count.
+fn all_pegs(rules: &Rules) -> Vec<Feedback> {
+    let code_len: CodeLen = rules.code_len();
+    (0..=code_len)
+        .flat_map(|exact| (0..=code_len - exact).map(move |misplaced| (exact, misplaced)))
+        .map(|(exact, misplaced)| Feedback::Pegs { exact, misplaced })
+        .filter(|feedback| feedback.is_valid_for(rules))
+        .collect()
+}
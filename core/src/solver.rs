@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use crate::{
+    deduction::Deductions,
+    enumeration::Codes,
+    feedback::Feedback,
+    game::{Code, Turn},
+    packed::PackedCode,
+    rules::Rules,
+    scheme::Scheme,
+    symmetry::Symmetries,
+};
+
+/// A strategy that picks the next guess from the turns played so far.
+pub trait Solver {
+    /// Returns the next guess, or `None` when no code is consistent with the history.
+    fn next_guess(&mut self, rules: &Rules, history: &[Turn]) -> Option<Code>;
+}
+
+/// Plays the first code that is consistent with every turn of the history.
+#[derive(Debug, Default)]
+pub struct ConsistentSolver;
+
+impl Solver for ConsistentSolver {
+    fn next_guess(&mut self, rules: &Rules, history: &[Turn]) -> Option<Code> {
+        candidates(rules, history).into_iter().next()
+    }
+}
+
+/// Knuth's minimax strategy: plays the code whose worst feedback leaves the
+/// fewest candidates, preferring codes that could still be the secret.
+///
+/// Only one guess of each class of [`Symmetries`] of the history is
+/// evaluated, and peg schemes are scored on [`PackedCode`]s when the rules
+/// allow it. With the `parallel` feature, guesses are evaluated across
+/// threads; the chosen guess is the same either way.
+#[derive(Debug, Default)]
+pub struct MinimaxSolver;
+
+impl Solver for MinimaxSolver {
+    fn next_guess(&mut self, rules: &Rules, history: &[Turn]) -> Option<Code> {
+        let candidates = candidates(rules, history);
+        if candidates.len() <= 2 {
+            return candidates.into_iter().next();
+        }
+
+        let packed: Option<Vec<PackedCode>> =
+            (PackedCode::fits(rules) && rules.scheme() != Scheme::Wordle).then(|| {
+                candidates
+                    .iter()
+                    .filter_map(|c| PackedCode::new(c))
+                    .collect()
+            });
+        // Related guesses score the same, and the first of each class in
+        // lexicographic order is the one kept, so the choice is unchanged.
+        let symmetries = Symmetries::new(rules, history);
+        let guesses: Vec<Code> = Codes::new(rules)
+            .filter(|guess| symmetries.is_representative(guess))
+            .collect();
+        let scores = map_codes(&guesses, |guess| {
+            let worst = match (&packed, PackedCode::new(guess)) {
+                (Some(packed), Some(packed_guess)) => {
+                    worst_case_packed(rules.scheme(), packed, &packed_guess)
+                }
+                _ => worst_case(rules, &candidates, guess),
+            };
+            // Both lists are in lexicographic order
+            (worst, candidates.binary_search(guess).is_ok())
+        });
+
+        // Picked in order, so ties go to the same guess however scores were computed
+        let mut best: Option<(usize, bool, Code)> = None;
+        for (guess, (worst, is_candidate)) in guesses.into_iter().zip(scores) {
+            let better = match &best {
+                None => true,
+                Some((best_worst, best_is_candidate, _)) => {
+                    worst < *best_worst
+                        || (worst == *best_worst && is_candidate && !best_is_candidate)
+                }
+            };
+            if better {
+                best = Some((worst, is_candidate, guess));
+            }
+        }
+        best.map(|(_, _, guess)| guess)
+    }
+}
+
+/// Lists every code allowed by the rules, in lexicographic order.
+///
+/// Use [`Codes`] to go through them without holding them all.
+pub fn all_codes(rules: &Rules) -> Vec<Code> {
+    Codes::new(rules).collect()
+}
+
+/// Returns `true` if `code` would have produced every feedback of the history.
+pub fn is_consistent(rules: &Rules, code: &Code, history: &[Turn]) -> bool {
+    let scheme = rules.scheme();
+    history
+        .iter()
+        .all(|turn| scheme.score(code, turn.guess()) == *turn.feedback())
+}
+
+/// Lists every code that could still be the secret given the history.
+///
+/// Only the codes admitted by the [`Deductions`] of the history are checked.
+pub fn candidates(rules: &Rules, history: &[Turn]) -> Vec<Code> {
+    let admitted = Deductions::new(rules, history).admitted_codes();
+    let consistent = map_codes(&admitted, |code| is_consistent(rules, code, history));
+    admitted
+        .into_iter()
+        .zip(consistent)
+        .filter_map(|(code, consistent)| consistent.then_some(code))
+        .collect()
+}
+
+/// Applies `f` to every code, across threads with the `parallel` feature.
+/// The results keep the order of the codes.
+#[cfg(feature = "parallel")]
+fn map_codes<T: Send>(codes: &[Code], f: impl Fn(&Code) -> T + Sync + Send) -> Vec<T> {
+    use rayon::prelude::*;
+    codes.par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn map_codes<T>(codes: &[Code], f: impl Fn(&Code) -> T) -> Vec<T> {
+    codes.iter().map(f).collect()
+}
+
+/// Size of the largest group of candidates sharing the same feedback for `guess`.
+fn worst_case(rules: &Rules, candidates: &[Code], guess: &Code) -> usize {
+    let scheme = rules.scheme();
+    let mut partition: HashMap<Feedback, usize> = HashMap::new();
+    for candidate in candidates {
+        *partition.entry(scheme.score(candidate, guess)).or_default() += 1;
+    }
+    partition.into_values().max().unwrap_or(0)
+}
+
+/// Same as [`worst_case`] for the peg schemes, on packed codes.
+fn worst_case_packed(scheme: Scheme, candidates: &[PackedCode], guess: &PackedCode) -> usize {
+    const KEYS: usize = PackedCode::MAX_LEN + 1;
+    let mut partition = [0; KEYS * KEYS];
+    for candidate in candidates {
+        let (exact, matched) = candidate.peg_counts(guess);
+        let key = match scheme {
+            Scheme::ExactOnly => exact as usize,
+            _ => exact as usize * KEYS + matched as usize,
+        };
+        partition[key] += 1;
+    }
+    partition.into_iter().max().unwrap_or(0)
+}
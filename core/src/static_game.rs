@@ -0,0 +1,214 @@
+//! Static Mastermind: every guess is submitted up front, then one final guess
+//! must name the secret.
+
+use std::collections::HashMap;
+
+use crate::{
+    feedback::Feedback,
+    game::{Code, Game, GameStatus, Turn},
+    rules::{Limit, Rules},
+    solver,
+    symmetry::Symmetries,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StaticError {
+    AlreadySubmitted,
+    NotSubmitted,
+    AlreadyFinished,
+    /// The rules do not accept the guess at this index.
+    InvalidCode {
+        index: usize,
+    },
+    /// The guesses and the final guess do not fit in the attempt limit.
+    TooManyGuesses,
+}
+
+pub struct StaticGame {
+    secret_code: Code,
+    rules: Rules,
+    turns: Option<Vec<Turn>>,
+    final_guess: Option<Code>,
+}
+
+impl StaticGame {
+    pub fn new(rules: Rules) -> Self {
+        let secret_code = Game::generate_secret_code(&rules);
+        Self::with_secret(rules, secret_code)
+    }
+
+    pub(crate) fn with_secret(rules: Rules, secret_code: Code) -> Self {
+        StaticGame {
+            secret_code,
+            rules,
+            turns: None,
+            final_guess: None,
+        }
+    }
+
+    /// Scores every guess at once. Can only be done once per game.
+    pub fn submit(&mut self, guesses: Vec<Code>) -> Result<&[Turn], StaticError> {
+        if self.turns.is_some() {
+            return Err(StaticError::AlreadySubmitted);
+        }
+        if let Some(index) = guesses.iter().position(|guess| !self.rules.accepts(guess)) {
+            return Err(StaticError::InvalidCode { index });
+        }
+        if let Limit::Attempts { count } = self.rules.limit()
+            && guesses.len() + 1 > *count as usize
+        {
+            return Err(StaticError::TooManyGuesses);
+        }
+
+        let turns = guesses
+            .into_iter()
+            .map(|guess| {
+                let feedback = self.rules.scheme().score(&self.secret_code, &guess);
+                Turn::new(guess, feedback)
+            })
+            .collect();
+        Ok(self.turns.insert(turns))
+    }
+
+    /// Plays the single final guess.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the final guess is the secret.
+    pub fn final_guess(&mut self, guess: Code) -> Result<bool, StaticError> {
+        if self.turns.is_none() {
+            return Err(StaticError::NotSubmitted);
+        }
+        if self.final_guess.is_some() {
+            return Err(StaticError::AlreadyFinished);
+        }
+        if !self.rules.accepts(&guess) {
+            return Err(StaticError::InvalidCode { index: 0 });
+        }
+        let won = guess == self.secret_code;
+        self.final_guess = Some(guess);
+        Ok(won)
+    }
+
+    /// The submitted guesses with their feedback, once submitted.
+    pub fn turns(&self) -> Option<&[Turn]> {
+        self.turns.as_deref()
+    }
+
+    pub fn status(&self) -> GameStatus {
+        match &self.final_guess {
+            Some(guess) if *guess == self.secret_code => GameStatus::Won,
+            Some(_) => GameStatus::Lost,
+            None => GameStatus::InProgress,
+        }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+}
+
+/// Finds a smallest set of guesses whose feedback tells every code apart, so
+/// that the final guess of a static game is always right.
+///
+/// The search is exhaustive and only practical for small rules.
+pub fn minimal_guess_set(rules: &Rules) -> Vec<Code> {
+    let codes = solver::all_codes(rules);
+    let feedbacks = Feedback::all_possible(rules).len();
+    let labels = vec![0; codes.len()];
+    // Every symbol and position plays the same role before the first guess,
+    // so the first guess only needs to be tried up to symmetry.
+    let symmetries = Symmetries::new(rules, &[]);
+    let first_guesses: Vec<usize> = (0..codes.len())
+        .filter(|&index| symmetries.is_representative(&codes[index]))
+        .collect();
+
+    for size in 0.. {
+        let mut chosen = Vec::new();
+        let search = Search {
+            rules,
+            codes: &codes,
+            feedbacks,
+        };
+        if search.run(&labels, size, &first_guesses, &mut chosen) {
+            return chosen
+                .into_iter()
+                .map(|index| codes[index].clone())
+                .collect();
+        }
+    }
+    unreachable!("guessing every code tells them all apart")
+}
+
+struct Search<'a> {
+    rules: &'a Rules,
+    codes: &'a [Code],
+    feedbacks: usize,
+}
+
+impl Search<'_> {
+    /// Depth-first search for `left` more guesses that split every class of
+    /// `labels` into single codes.
+    fn run(
+        &self,
+        labels: &[usize],
+        left: usize,
+        options: &[usize],
+        chosen: &mut Vec<usize>,
+    ) -> bool {
+        let largest = largest_class(labels);
+        if largest <= 1 {
+            return true;
+        }
+        // Each guess splits a class into at most one part per feedback
+        if (self.feedbacks as u128).saturating_pow(left as u32) < largest as u128 {
+            return false;
+        }
+
+        let classes = class_count(labels);
+        for &guess in options {
+            let refined = refine(self.rules, self.codes, labels, &self.codes[guess]);
+            if class_count(&refined) == classes {
+                continue;
+            }
+            chosen.push(guess);
+            // The first guess is canonical, the others are tried in increasing order
+            let start = if chosen.len() == 1 { 0 } else { guess + 1 };
+            let next: Vec<usize> = (start..self.codes.len())
+                .filter(|index| !chosen.contains(index))
+                .collect();
+            if self.run(&refined, left - 1, &next, chosen) {
+                return true;
+            }
+            chosen.pop();
+        }
+        false
+    }
+}
+
+/// Splits each class of codes by the feedback they give to `guess`.
+fn refine(rules: &Rules, codes: &[Code], labels: &[usize], guess: &Code) -> Vec<usize> {
+    let scheme = rules.scheme();
+    let mut ids: HashMap<(usize, Feedback), usize> = HashMap::new();
+    codes
+        .iter()
+        .zip(labels)
+        .map(|(code, &label)| {
+            let next = ids.len();
+            *ids.entry((label, scheme.score(code, guess)))
+                .or_insert(next)
+        })
+        .collect()
+}
+
+fn largest_class(labels: &[usize]) -> usize {
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for &label in labels {
+        *sizes.entry(label).or_default() += 1;
+    }
+    sizes.into_values().max().unwrap_or(0)
+}
+
+fn class_count(labels: &[usize]) -> usize {
+    labels.iter().max().map_or(0, |&max| max + 1)
+}
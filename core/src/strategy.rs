@@ -0,0 +1,399 @@
+//! Decision trees telling what to guess after each feedback.
+//!
+//! In text, each node is one line indented by two spaces per guess already
+//! played. The first line is the opening guess; the others give the feedback
+//! that leads to them, then the guess. A `*` marks guesses that can be the
+//! secret:
+//!
+//! ```text
+//! 0 0 1
+//!   0 0 -> 2 2 2 *
+//!   1 0 -> 0 2 2 *
+//!     0 2 -> 2 0 2 *
+//! ```
+//!
+//! Trees can also be exported as JSON or as a Graphviz DOT graph, and played
+//! back by a [`BookSolver`].
+
+use std::fmt::{self, Write};
+
+use crate::{
+    feedback::{Feedback, FeedbackError},
+    game::{Code, Turn},
+    replay::{self, ReplayError},
+    rules::Rules,
+    solver::{self, Solver},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum StrategyError {
+    Malformed { line: usize },
+    InvalidFeedback { line: usize, error: FeedbackError },
+}
+
+impl StrategyError {
+    fn from_replay(error: ReplayError, line: usize) -> Self {
+        match error {
+            ReplayError::InvalidFeedback { line, error } => {
+                StrategyError::InvalidFeedback { line, error }
+            }
+            _ => StrategyError::Malformed { line },
+        }
+    }
+}
+
+/// A guess, and the strategy to follow after each feedback other than a win.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Strategy {
+    guess: Code,
+    solves: bool,
+    branches: Vec<(Feedback, Strategy)>,
+}
+
+impl Strategy {
+    /// `solves` tells whether the guess is one of the secrets still possible.
+    pub fn new(guess: Code, solves: bool, branches: Vec<(Feedback, Strategy)>) -> Self {
+        Strategy {
+            guess,
+            solves,
+            branches,
+        }
+    }
+
+    pub fn guess(&self) -> &Code {
+        &self.guess
+    }
+
+    /// Returns `true` if the guess can be the secret.
+    pub fn solves(&self) -> bool {
+        self.solves
+    }
+
+    pub fn branches(&self) -> &[(Feedback, Strategy)] {
+        &self.branches
+    }
+
+    /// The strategy to follow after `feedback`, if the tree covers it.
+    pub fn branch(&self, feedback: &Feedback) -> Option<&Strategy> {
+        self.branches
+            .iter()
+            .find(|(f, _)| f == feedback)
+            .map(|(_, strategy)| strategy)
+    }
+
+    /// The strategy to follow after the history, if the history played its
+    /// guesses and the tree covers its feedback.
+    pub fn follow(&self, history: &[Turn]) -> Option<&Strategy> {
+        let mut node = self;
+        for turn in history {
+            if *turn.guess() != node.guess {
+                return None;
+            }
+            node = node.branch(turn.feedback())?;
+        }
+        Some(node)
+    }
+
+    /// The tree cut after its first `guesses` guesses.
+    pub fn truncated(&self, guesses: usize) -> Strategy {
+        let branches = if guesses <= 1 {
+            Vec::new()
+        } else {
+            self.branches
+                .iter()
+                .map(|(feedback, branch)| (feedback.clone(), branch.truncated(guesses - 1)))
+                .collect()
+        };
+        Strategy::new(self.guess.clone(), self.solves, branches)
+    }
+
+    /// Plays the solver against every secret the rules allow and records
+    /// its guesses.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the solver gives up, or plays a guess twice, before some
+    /// secret is found.
+    pub fn from_solver<S: Solver + ?Sized>(solver: &mut S, rules: &Rules) -> Option<Self> {
+        explore(
+            solver,
+            rules,
+            &mut Vec::new(),
+            solver::all_codes(rules),
+            usize::MAX,
+        )
+    }
+
+    /// Like [`Strategy::from_solver`], but stops after the first `guesses`
+    /// guesses, as needed for an opening book.
+    pub fn opening<S: Solver + ?Sized>(
+        solver: &mut S,
+        rules: &Rules,
+        guesses: usize,
+    ) -> Option<Self> {
+        explore(
+            solver,
+            rules,
+            &mut Vec::new(),
+            solver::all_codes(rules),
+            guesses,
+        )
+    }
+
+    /// How many secrets are solved with each number of guesses, starting
+    /// with one guess.
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        self.count(1, &mut histogram);
+        histogram
+    }
+
+    /// Number of secrets the tree solves.
+    pub fn secrets(&self) -> usize {
+        self.histogram().iter().sum()
+    }
+
+    /// Most guesses needed for a secret.
+    pub fn worst_case(&self) -> usize {
+        self.histogram().len()
+    }
+
+    /// Guesses needed over all secrets.
+    pub fn total_guesses(&self) -> usize {
+        self.histogram()
+            .iter()
+            .enumerate()
+            .map(|(index, count)| (index + 1) * count)
+            .sum()
+    }
+
+    /// Reads a tree written by the [`fmt::Display`] implementation.
+    pub fn parse(s: &str, rules: &Rules) -> Result<Self, StrategyError> {
+        // Nodes whose branches are still being read, with their depth
+        let mut open: Vec<(usize, Option<Feedback>, Strategy)> = Vec::new();
+        let mut root: Option<Strategy> = None;
+        for (index, text) in s.lines().enumerate() {
+            let line = index + 1;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let indent = text.len() - text.trim_start().len();
+            if indent % 2 != 0 || root.is_some() {
+                return Err(StrategyError::Malformed { line });
+            }
+            let depth = indent / 2;
+            let (feedback, node) = match text.trim().split_once("->") {
+                Some((feedback, node)) if depth > 0 => (
+                    Some(
+                        replay::parse_feedback(feedback, rules, line)
+                            .map_err(|error| StrategyError::from_replay(error, line))?,
+                    ),
+                    node,
+                ),
+                None if depth == 0 && open.is_empty() => (None, text.trim()),
+                _ => return Err(StrategyError::Malformed { line }),
+            };
+            let (node, solves) = match node.trim().strip_suffix('*') {
+                Some(node) => (node, true),
+                None => (node, false),
+            };
+            let guess = replay::parse_code(node, rules, line)
+                .map_err(|error| StrategyError::from_replay(error, line))?;
+
+            while open.last().is_some_and(|(d, _, _)| *d >= depth) {
+                close(&mut open, &mut root);
+            }
+            if open.last().is_some_and(|(d, _, _)| *d + 1 != depth) {
+                return Err(StrategyError::Malformed { line });
+            }
+            open.push((depth, feedback, Strategy::new(guess, solves, Vec::new())));
+        }
+        while !open.is_empty() {
+            close(&mut open, &mut root);
+        }
+        root.ok_or(StrategyError::Malformed { line: 0 })
+    }
+
+    /// The tree as a single line of JSON, e.g.
+    /// `{"guess":[0,0,1],"solves":false,"branches":[{"feedback":{"exact":0,"misplaced":0},"strategy":...}]}`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    /// The tree as a Graphviz graph. Guesses that can be the secret are drawn
+    /// with a double border.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph strategy {\n  node [shape=box];\n");
+        self.write_dot(&mut dot, &mut 0);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn count(&self, depth: usize, histogram: &mut Vec<usize>) {
+        if self.solves {
+            if histogram.len() < depth {
+                histogram.resize(depth, 0);
+            }
+            histogram[depth - 1] += 1;
+        }
+        for (_, branch) in &self.branches {
+            branch.count(depth + 1, histogram);
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let star = if self.solves { " *" } else { "" };
+        writeln!(f, "{}{}", replay::format_code(&self.guess), star)?;
+        for (feedback, branch) in &self.branches {
+            write!(
+                f,
+                "{:indent$}{} -> ",
+                "",
+                replay::format_feedback(feedback),
+                indent = 2 * (depth + 1)
+            )?;
+            branch.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Asks the solver for a guess after `history`, then explores each feedback
+/// that one of `secrets` gives, for at most `guesses` guesses.
+fn explore<S: Solver + ?Sized>(
+    solver: &mut S,
+    rules: &Rules,
+    history: &mut Vec<Turn>,
+    secrets: Vec<Code>,
+    guesses: usize,
+) -> Option<Strategy> {
+    let guess = solver.next_guess(rules, history)?;
+    if history.iter().any(|turn| *turn.guess() == guess) {
+        return None;
+    }
+    let solves = secrets.contains(&guess);
+    if guesses <= 1 {
+        return Some(Strategy::new(guess, solves, Vec::new()));
+    }
+    let scheme = rules.scheme();
+    let win = scheme.score(&guess, &guess);
+    let scored: Vec<(Feedback, &Code)> = secrets
+        .iter()
+        .map(|secret| (scheme.score(secret, &guess), secret))
+        .collect();
+    let mut branches = Vec::new();
+    for feedback in scheme.all_possible(rules) {
+        if feedback == win {
+            continue;
+        }
+        let class: Vec<Code> = scored
+            .iter()
+            .filter(|(f, _)| *f == feedback)
+            .map(|(_, secret)| (*secret).clone())
+            .collect();
+        if class.is_empty() {
+            continue;
+        }
+        history.push(Turn::new(guess.clone(), feedback.clone()));
+        let branch = explore(solver, rules, history, class, guesses - 1);
+        history.pop();
+        branches.push((feedback, branch?));
+    }
+    Some(Strategy::new(guess, solves, branches))
+}
+
+/// Attaches the last open node to its parent, or makes it the root.
+fn close(open: &mut Vec<(usize, Option<Feedback>, Strategy)>, root: &mut Option<Strategy>) {
+    let Some((_, feedback, node)) = open.pop() else {
+        return;
+    };
+    match (open.last_mut(), feedback) {
+        (Some((_, _, parent)), Some(feedback)) => parent.branches.push((feedback, node)),
+        _ => *root = Some(node),
+    }
+}
+
+impl Strategy {
+    fn write_json(&self, json: &mut String) {
+        let guess: Vec<String> = self.guess.iter().map(|s| s.to_string()).collect();
+        let _ = write!(
+            json,
+            "{{\"guess\":[{}],\"solves\":{},\"branches\":[",
+            guess.join(","),
+            self.solves
+        );
+        for (index, (feedback, branch)) in self.branches.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{{\"feedback\":{},\"strategy\":",
+                replay::feedback_json(feedback)
+            );
+            branch.write_json(json);
+            json.push('}');
+        }
+        json.push_str("]}");
+    }
+
+    /// Writes the node and its branches, numbering nodes from `next`.
+    fn write_dot(&self, dot: &mut String, next: &mut usize) -> usize {
+        let id = *next;
+        *next += 1;
+        let border = if self.solves { ", peripheries=2" } else { "" };
+        let _ = writeln!(
+            dot,
+            "  n{} [label=\"{}\"{}];",
+            id,
+            replay::format_code(&self.guess),
+            border
+        );
+        for (feedback, branch) in &self.branches {
+            let child = branch.write_dot(dot, next);
+            let _ = writeln!(
+                dot,
+                "  n{} -> n{} [label=\"{}\"];",
+                id,
+                child,
+                replay::format_feedback(feedback)
+            );
+        }
+        id
+    }
+}
+
+/// Plays the guesses of a strategy, such as an opening book, while the game
+/// stays in the tree, then leaves the rest to another solver.
+#[derive(Debug, Clone)]
+pub struct BookSolver<S> {
+    book: Strategy,
+    fallback: S,
+}
+
+impl<S: Solver> BookSolver<S> {
+    pub fn new(book: Strategy, fallback: S) -> Self {
+        BookSolver { book, fallback }
+    }
+
+    pub fn book(&self) -> &Strategy {
+        &self.book
+    }
+}
+
+impl<S: Solver> Solver for BookSolver<S> {
+    fn next_guess(&mut self, rules: &Rules, history: &[Turn]) -> Option<Code> {
+        match self.book.follow(history) {
+            Some(node) => Some(node.guess.clone()),
+            None => self.fallback.next_guess(rules, history),
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
@@ -0,0 +1,169 @@
+//! Symmetries of the history: colour and position permutations under which
+//! every turn stays the same.
+//!
+//! Codes related by such a permutation are equally good guesses, so solvers
+//! only need to evaluate one code of each class.
+
+use crate::{
+    feedback::Feedback,
+    game::{Code, Turn},
+    rules::Rules,
+};
+
+/// Position permutations are only tried up to this code length, since there
+/// are `code_len!` of them. Longer codes keep their colour symmetries.
+pub const MAX_PERMUTED_POSITIONS: usize = 7;
+
+/// A position permutation, with the colour mapping it forces on the colours
+/// used by the history.
+#[derive(Debug, Clone)]
+struct Permutation {
+    positions: Vec<usize>,
+    colors: Vec<Option<u8>>,
+}
+
+/// The colour and position permutations that leave every turn of the history
+/// unchanged, so that any two codes they relate are interchangeable.
+///
+/// Colours that no guess used can be swapped freely; the others must be
+/// permuted along with the positions so that each guess maps onto itself.
+#[derive(Debug, Clone)]
+pub struct Symmetries {
+    permutations: Vec<Permutation>,
+    free_colors: Vec<u8>,
+}
+
+impl Symmetries {
+    pub fn new(rules: &Rules, history: &[Turn]) -> Self {
+        let code_len = rules.code_len() as usize;
+        let used: Vec<u8> = {
+            let mut used: Vec<u8> = history.iter().flat_map(|t| t.guess().clone()).collect();
+            used.sort_unstable();
+            used.dedup();
+            used
+        };
+        let free_colors = (0..rules.available_symbols())
+            .filter(|symbol| !used.contains(symbol))
+            .collect();
+
+        let position_permutations = if code_len <= MAX_PERMUTED_POSITIONS {
+            permutations(code_len)
+        } else {
+            vec![(0..code_len).collect()]
+        };
+        let permutations = position_permutations
+            .into_iter()
+            .filter_map(|positions| {
+                forced_colors(&positions, history).map(|colors| Permutation { positions, colors })
+            })
+            .collect();
+        Symmetries {
+            permutations,
+            free_colors,
+        }
+    }
+
+    /// Colours that no guess of the history used.
+    pub fn free_colors(&self) -> &[u8] {
+        &self.free_colors
+    }
+
+    /// Number of position permutations that, with some colour permutation,
+    /// leave the history unchanged.
+    pub fn position_permutations(&self) -> usize {
+        self.permutations.len()
+    }
+
+    /// The smallest code, in lexicographic order, related to `code`.
+    pub fn canonical(&self, code: &Code) -> Code {
+        self.permutations
+            .iter()
+            .map(|permutation| self.apply(permutation, code))
+            .min()
+            .unwrap_or_else(|| code.clone())
+    }
+
+    /// Returns `true` if no related code comes before `code` in lexicographic
+    /// order, i.e. if `code` represents its class.
+    pub fn is_representative(&self, code: &Code) -> bool {
+        self.permutations
+            .iter()
+            .all(|permutation| self.apply(permutation, code) >= *code)
+    }
+
+    /// Maps `code` with the permutation, then renames its free colours to the
+    /// smallest ones in order of appearance.
+    fn apply(&self, permutation: &Permutation, code: &Code) -> Code {
+        let mut mapped = vec![0; code.len()];
+        for (position, &symbol) in code.iter().enumerate() {
+            mapped[permutation.positions[position]] =
+                permutation.colors[symbol as usize].unwrap_or(symbol);
+        }
+        let mut renamed: Vec<(u8, u8)> = Vec::new();
+        for symbol in mapped.iter_mut() {
+            if !self.free_colors.contains(symbol) {
+                continue;
+            }
+            *symbol = match renamed.iter().find(|(from, _)| from == symbol) {
+                Some(&(_, to)) => to,
+                None => {
+                    let to = self.free_colors[renamed.len()];
+                    renamed.push((*symbol, to));
+                    to
+                }
+            };
+        }
+        mapped
+    }
+}
+
+/// The colour mapping under which every guess maps onto itself when its
+/// positions are permuted, if there is one.
+///
+/// Position `i` moves to `positions[i]`, so colour `guess[i]` must become
+/// `guess[positions[i]]`. Wordle marks must also be left in place.
+fn forced_colors(positions: &[usize], history: &[Turn]) -> Option<Vec<Option<u8>>> {
+    let mut colors: Vec<Option<u8>> = vec![None; u8::MAX as usize + 1];
+    let mut preimages: Vec<Option<u8>> = vec![None; u8::MAX as usize + 1];
+    for turn in history {
+        let guess = turn.guess();
+        if guess.len() != positions.len() {
+            return None;
+        }
+        for (from, &to) in positions.iter().enumerate() {
+            let (a, b) = (guess[from], guess[to]);
+            if *colors[a as usize].get_or_insert(b) != b
+                || *preimages[b as usize].get_or_insert(a) != a
+            {
+                return None;
+            }
+        }
+        if let Feedback::Marks(marks) = turn.feedback()
+            && positions
+                .iter()
+                .enumerate()
+                .any(|(from, &to)| marks[from] != marks[to])
+        {
+            return None;
+        }
+    }
+    Some(colors)
+}
+
+/// Every permutation of `0..len`.
+fn permutations(len: usize) -> Vec<Vec<usize>> {
+    let mut all = vec![Vec::new()];
+    for item in 0..len {
+        all = all
+            .into_iter()
+            .flat_map(|permutation: Vec<usize>| {
+                (0..=permutation.len()).map(move |index| {
+                    let mut longer = permutation.clone();
+                    longer.insert(index, item);
+                    longer
+                })
+            })
+            .collect();
+    }
+    all
+}
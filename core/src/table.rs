@@ -0,0 +1,190 @@
+//! Precomputed feedback for every secret and guess of small rules.
+//!
+//! Saved tables start with a text line naming the rules, then hold one byte
+//! per secret and guess, guess by guess:
+//!
+//! ```text
+//! mastermind-table 1 4 6 classic
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    sync::OnceLock,
+};
+
+use crate::{feedback::Feedback, game::Code, replay, rules::Rules, solver};
+
+const HEADER: &str = "mastermind-table 1";
+
+#[derive(Debug)]
+pub enum TableError {
+    /// The whole table would take more bytes than allowed.
+    TooLarge {
+        bytes: usize,
+    },
+    /// The scheme gives more feedbacks than fit in a byte.
+    TooManyFeedbacks,
+    /// The saved table was built for other rules.
+    WrongRules,
+    Malformed,
+    Io(io::Error),
+}
+
+impl From<io::Error> for TableError {
+    fn from(error: io::Error) -> Self {
+        TableError::Io(error)
+    }
+}
+
+/// Maps each secret and guess, by their index among the codes allowed by the
+/// rules, to the id of their feedback.
+///
+/// The memory for the whole table is checked up front, but each guess's row
+/// is only computed the first time it is asked for.
+pub struct FeedbackTable {
+    rules: Rules,
+    codes: Vec<Code>,
+    feedbacks: Vec<Feedback>,
+    rows: Vec<OnceLock<Box<[u8]>>>,
+}
+
+impl FeedbackTable {
+    /// Prepares an empty table for the rules.
+    ///
+    /// # Returns
+    ///
+    /// An error if the full table would take more than `max_bytes`.
+    pub fn new(rules: &Rules, max_bytes: usize) -> Result<Self, TableError> {
+        let codes = solver::all_codes(rules);
+        let bytes = codes.len().saturating_mul(codes.len());
+        if bytes > max_bytes {
+            return Err(TableError::TooLarge { bytes });
+        }
+        let feedbacks = Feedback::all_possible(rules);
+        if feedbacks.len() > u8::MAX as usize + 1 {
+            return Err(TableError::TooManyFeedbacks);
+        }
+        Ok(FeedbackTable {
+            rules: rules.clone(),
+            rows: (0..codes.len()).map(|_| OnceLock::new()).collect(),
+            codes,
+            feedbacks,
+        })
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Every code allowed by the rules, in lexicographic order.
+    pub fn codes(&self) -> &[Code] {
+        &self.codes
+    }
+
+    pub fn index_of(&self, code: &Code) -> Option<usize> {
+        self.codes.binary_search(code).ok()
+    }
+
+    /// Every feedback the scheme can give, indexed by id.
+    pub fn feedbacks(&self) -> &[Feedback] {
+        &self.feedbacks
+    }
+
+    pub fn id_of(&self, feedback: &Feedback) -> Option<u8> {
+        self.feedbacks
+            .iter()
+            .position(|f| f == feedback)
+            .map(|id| id as u8)
+    }
+
+    /// Id of the feedback `guess` gets against `secret`, both given by index.
+    pub fn feedback_id(&self, secret: usize, guess: usize) -> u8 {
+        self.row(guess)[secret]
+    }
+
+    pub fn feedback(&self, secret: usize, guess: usize) -> &Feedback {
+        &self.feedbacks[self.feedback_id(secret, guess) as usize]
+    }
+
+    /// The feedback ids of one guess against every secret, built on first use.
+    pub fn row(&self, guess: usize) -> &[u8] {
+        self.rows[guess].get_or_init(|| {
+            let ids: HashMap<&Feedback, u8> = self
+                .feedbacks
+                .iter()
+                .enumerate()
+                .map(|(id, feedback)| (feedback, id as u8))
+                .collect();
+            let scheme = self.rules.scheme();
+            let guess = &self.codes[guess];
+            self.codes
+                .iter()
+                .map(|secret| ids[&scheme.score(secret, guess)])
+                .collect()
+        })
+    }
+
+    /// Number of rows computed so far.
+    pub fn built_rows(&self) -> usize {
+        self.rows.iter().filter(|row| row.get().is_some()).count()
+    }
+
+    /// Writes the table, building every row first.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{} {} {} {}",
+            HEADER,
+            self.rules.code_len(),
+            self.rules.available_symbols(),
+            replay::scheme_name(self.rules.scheme())
+        )?;
+        for guess in 0..self.codes.len() {
+            writer.write_all(self.row(guess))?;
+        }
+        writer.flush()
+    }
+
+    /// Reads a table saved by [`FeedbackTable::save`] for the same rules.
+    ///
+    /// The time limit or attempt limit of the rules does not matter.
+    pub fn load<R: BufRead>(
+        mut reader: R,
+        rules: &Rules,
+        max_bytes: usize,
+    ) -> Result<Self, TableError> {
+        let table = Self::new(rules, max_bytes)?;
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let words: Vec<&str> = header.trim_end().rsplitn(4, ' ').collect();
+        let [scheme, symbols, code_len, HEADER] = words[..] else {
+            return Err(TableError::Malformed);
+        };
+        let same_rules = code_len == rules.code_len().to_string()
+            && symbols == rules.available_symbols().to_string()
+            && replay::parse_scheme(scheme) == Some(rules.scheme());
+        if !same_rules {
+            return Err(TableError::WrongRules);
+        }
+
+        let size = table.codes.len();
+        for row in &table.rows {
+            let mut ids = vec![0; size].into_boxed_slice();
+            reader
+                .read_exact(&mut ids)
+                .map_err(|error| match error.kind() {
+                    io::ErrorKind::UnexpectedEof => TableError::Malformed,
+                    _ => TableError::Io(error),
+                })?;
+            if ids.iter().any(|&id| id as usize >= table.feedbacks.len()) {
+                return Err(TableError::Malformed);
+            }
+            let _ = row.set(ids);
+        }
+        if reader.read(&mut [0])? != 0 {
+            return Err(TableError::Malformed);
+        }
+        Ok(table)
+    }
+}
@@ -1,8 +1,27 @@
 #[cfg(test)]
 mod tests {
-    use crate::feedback::Feedback;
-    use crate::game::{Code, Game};
+    use crate::feedback::{Feedback, FeedbackError};
+    use crate::game::{Code, Game, Turn};
     use crate::rules::{Limit, Rules, RulesError};
+    use crate::solver::Solver;
+
+    /// Plays the solver against the secret, giving up after `max_guesses`,
+    /// and returns the turns played.
+    fn solver_game<S: Solver + ?Sized>(
+        solver: &mut S,
+        rules: &Rules,
+        secret: &Code,
+        max_guesses: usize,
+    ) -> Vec<Turn> {
+        let mut history: Vec<Turn> = Vec::new();
+        while history.len() < max_guesses && history.last().is_none_or(|t| t.guess() != secret) {
+            let guess = solver.next_guess(rules, &history).unwrap();
+            assert!(rules.accepts(&guess));
+            let feedback = rules.scheme().score(secret, &guess);
+            history.push(Turn::new(guess, feedback));
+        }
+        history
+    }
 
     // Tests for Rules module
     mod rules_tests {
@@ -150,7 +169,83 @@ mod tests {
 
             let debug_str = format!("{:?}", feedback);
             assert!(debug_str.contains("exact: 1")); // only position 0 matches
-            assert!(debug_str.contains("misplaced: 3")); // current implementation counts secret elements found in guess
+            assert!(debug_str.contains("misplaced: 0")); // the single 1 in guess is already matched
+        }
+
+        #[test]
+        fn test_feedback_duplicates_matched_once() {
+            let secret: Code = vec![1, 1, 2, 2];
+            let guess: Code = vec![1, 2, 1, 1];
+            let feedback = Feedback::new(&secret, &guess);
+
+            assert_eq!(feedback.exact(), 1);
+            assert_eq!(feedback.misplaced(), Some(2));
+        }
+
+        #[test]
+        fn test_feedback_all_possible_classic() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let all = Feedback::all_possible(&rules);
+
+            // Every pair with exact + misplaced <= 4, except 3 exact and 1 misplaced
+            assert_eq!(all.len(), 14);
+            assert!(
+                !all.iter()
+                    .any(|f| f.exact() == 3 && f.misplaced() == Some(1))
+            );
+        }
+
+        #[test]
+        fn test_feedback_all_possible_matches_enumeration() {
+            for (code_len, symbols) in [
+                (1, 1),
+                (3, 1),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (2, 3),
+                (3, 3),
+                (4, 3),
+            ] {
+                let rules = Rules::new(code_len, Limit::NoLimitation, symbols).unwrap();
+                let codes = crate::solver::all_codes(&rules);
+                let mut reached: Vec<Feedback> = Vec::new();
+                for secret in &codes {
+                    for guess in &codes {
+                        let feedback = Feedback::new(secret, guess);
+                        if !reached.contains(&feedback) {
+                            reached.push(feedback);
+                        }
+                    }
+                }
+
+                let all = Feedback::all_possible(&rules);
+                assert_eq!(all.len(), reached.len(), "{}x{}", code_len, symbols);
+                assert!(reached.iter().all(|f| f.is_valid_for(&rules)));
+            }
+        }
+
+        #[test]
+        fn test_feedback_from_counts() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+
+            let feedback = Feedback::from_counts(2, 1, &rules).unwrap();
+            assert_eq!(feedback.exact(), 2);
+            assert_eq!(feedback.misplaced(), Some(1));
+            assert_eq!(
+                Feedback::from_counts(3, 2, &rules),
+                Err(FeedbackError::TooManyPegs)
+            );
+            assert_eq!(
+                Feedback::from_counts(3, 1, &rules),
+                Err(FeedbackError::Unreachable)
+            );
+
+            let two_symbols = Rules::new(4, Limit::NoLimitation, 2).unwrap();
+            assert_eq!(
+                Feedback::from_counts(0, 3, &two_symbols),
+                Err(FeedbackError::Unreachable)
+            );
         }
 
         #[test]
@@ -177,6 +272,9 @@ mod tests {
     // Tests for Game module
     mod game_tests {
         use super::*;
+        use crate::multi::MultiGame;
+        use crate::scheme::Scheme;
+        use crate::static_game::{StaticError, StaticGame};
 
         fn create_test_rules() -> Rules {
             Rules::new(4, Limit::Attempts { count: 10 }, 6).unwrap()
@@ -190,6 +288,17 @@ mod tests {
             // Secret code should be generated (we can't test the exact value due to randomness)
         }
 
+        #[test]
+        fn test_game_with_chosen_secret() {
+            let rules = create_test_rules();
+            assert!(Game::with_secret(rules.clone(), vec![5, 0, 5, 1]).is_some());
+            assert!(Game::with_secret(rules.clone(), vec![1, 2, 3]).is_none());
+            assert!(Game::with_secret(rules.clone(), vec![1, 2, 3, 6]).is_none());
+
+            let bulls = rules.with_scheme(Scheme::BullsAndCows).unwrap();
+            assert!(Game::with_secret(bulls, vec![5, 0, 5, 1]).is_none());
+        }
+
         #[test]
         fn test_game_valid_guess() {
             let rules = create_test_rules();
@@ -220,14 +329,30 @@ mod tests {
             assert!(feedback.is_none());
         }
 
+        #[test]
+        fn test_game_invalid_guess_symbol() {
+            let rules = create_test_rules();
+            assert!(!rules.accepts(&[1, 2, 3, 6]));
+            let mut game = Game::new(rules.clone());
+            assert_eq!(game.guess(vec![9, 9, 9, 9]), None);
+
+            let mut multi = MultiGame::new(rules.clone(), 2).unwrap();
+            assert_eq!(multi.guess(vec![0, 0, 6, 0]), None);
+            let mut fixed = StaticGame::new(rules);
+            assert!(matches!(
+                fixed.submit(vec![vec![0, 0, 0, 0], vec![6, 0, 0, 0]]),
+                Err(StaticError::InvalidCode { index: 1 })
+            ));
+        }
+
         #[test]
         fn test_game_multiple_guesses() {
             let rules = create_test_rules();
             let mut game = Game::new(rules);
 
             let guess1: Code = vec![1, 2, 3, 4];
-            let guess2: Code = vec![5, 6, 1, 2];
-            let guess3: Code = vec![3, 4, 5, 6];
+            let guess2: Code = vec![5, 0, 1, 2];
+            let guess3: Code = vec![3, 4, 5, 0];
 
             let feedback1 = game.guess(guess1);
             let feedback2 = game.guess(guess2);
@@ -513,4 +638,2123 @@ mod tests {
             // This just tests that games are independent
         }
     }
+
+    // Tests for Solver module
+    mod solver_tests {
+        use super::*;
+        use crate::game::Turn;
+        use crate::solver::{self, ConsistentSolver, MinimaxSolver, Solver};
+
+        #[test]
+        fn test_all_codes_count_and_order() {
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            let codes = solver::all_codes(&rules);
+
+            assert_eq!(codes.len(), 9);
+            assert_eq!(codes[0], vec![0, 0]);
+            assert_eq!(codes[1], vec![0, 1]);
+            assert_eq!(codes[8], vec![2, 2]);
+        }
+
+        #[test]
+        fn test_candidates_match_history() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let secret: Code = vec![1, 2, 3];
+            let guess: Code = vec![1, 1, 2];
+            let history = vec![Turn::new(guess.clone(), Feedback::new(&secret, &guess))];
+
+            let candidates = solver::candidates(&rules, &history);
+            assert!(candidates.contains(&secret));
+            assert!(
+                candidates
+                    .iter()
+                    .all(|code| solver::is_consistent(&rules, code, &history))
+            );
+        }
+
+        #[test]
+        fn test_solvers_find_secret() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let solvers: Vec<Box<dyn Solver>> =
+                vec![Box::new(ConsistentSolver), Box::new(MinimaxSolver)];
+
+            for mut solver in solvers {
+                let mut game = Game::with_secret(rules.clone(), vec![3, 0, 2]).unwrap();
+                let mut found = false;
+                for _ in 0..10 {
+                    let guess = solver.next_guess(game.rules(), game.state().history());
+                    let feedback = game.guess(guess.unwrap()).unwrap();
+                    if feedback.exact() == 3 {
+                        found = true;
+                        break;
+                    }
+                }
+                assert!(found);
+            }
+        }
+        /// Knuth's rule written out plainly: the first code, in order, with the
+        /// smallest worst case, candidates first.
+        fn reference_minimax(rules: &Rules, history: &[Turn]) -> Option<Code> {
+            let candidates = solver::candidates(rules, history);
+            if candidates.len() <= 2 {
+                return candidates.first().cloned();
+            }
+            solver::all_codes(rules).into_iter().min_by_key(|guess| {
+                let mut sizes: std::collections::HashMap<Feedback, usize> = Default::default();
+                for candidate in &candidates {
+                    *sizes.entry(Feedback::new(candidate, guess)).or_default() += 1;
+                }
+                let worst = sizes.into_values().max().unwrap();
+                (worst, !candidates.contains(guess))
+            })
+        }
+
+        #[test]
+        fn test_minimax_matches_reference() {
+            // Runs with and without the `parallel` feature
+            let rules = Rules::new(4, Limit::NoLimitation, 4).unwrap();
+            let secret: Code = vec![3, 1, 1, 0];
+            let mut history = Vec::new();
+            loop {
+                let guess = MinimaxSolver.next_guess(&rules, &history).unwrap();
+                assert_eq!(Some(&guess), reference_minimax(&rules, &history).as_ref());
+                if guess == secret {
+                    break;
+                }
+                history.push(Turn::new(guess.clone(), Feedback::new(&secret, &guess)));
+            }
+        }
+    }
+
+    // Tests for Hint module
+    mod hint_tests {
+        use super::*;
+        use crate::hint::{Hint, HintLevel};
+
+        #[test]
+        fn test_hint_count_without_history() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let mut game = Game::new(rules);
+
+            assert_eq!(
+                game.hint(HintLevel::Count),
+                Some(Hint::Remaining { candidates: 64 })
+            );
+        }
+
+        #[test]
+        fn test_hint_eliminate_absent_symbol() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let mut game = Game::with_secret(rules, vec![1, 2, 3]).unwrap();
+
+            // No symbol is known to be absent before the first guess
+            assert_eq!(game.hint(HintLevel::Eliminate), None);
+
+            game.guess(vec![0, 0, 0]);
+            assert_eq!(
+                game.hint(HintLevel::Eliminate),
+                Some(Hint::Absent { symbol: 0 })
+            );
+            // The same symbol is not eliminated twice
+            assert_eq!(game.hint(HintLevel::Eliminate), None);
+        }
+
+        #[test]
+        fn test_hint_suggestion_is_candidate_when_one_left() {
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            let mut game = Game::with_secret(rules, vec![2, 1]).unwrap();
+
+            game.guess(vec![1, 2]);
+            game.guess(vec![2, 0]);
+            assert_eq!(
+                game.hint(HintLevel::Suggest),
+                Some(Hint::Suggestion { guess: vec![2, 1] })
+            );
+        }
+
+        #[test]
+        fn test_hints_are_recorded() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let mut game = Game::with_secret(rules, vec![1, 2, 3]).unwrap();
+
+            game.hint(HintLevel::Count);
+            game.guess(vec![0, 1, 2]);
+            game.hint(HintLevel::Suggest);
+
+            let hints = game.state().hints();
+            assert_eq!(hints.len(), 2);
+            assert_eq!(hints[0].turn(), 0);
+            assert_eq!(hints[0].hint().level(), HintLevel::Count);
+            assert_eq!(hints[1].turn(), 1);
+            assert_eq!(hints[1].hint().level(), HintLevel::Suggest);
+        }
+    }
+
+    // Tests for Deduction module
+    mod deduction_tests {
+        use super::*;
+        use crate::deduction::{Deductions, Fact};
+        use crate::game::Turn;
+        use crate::solver::{self, MinimaxSolver, Solver};
+
+        fn turn(secret: &Code, guess: Code) -> Turn {
+            let feedback = Feedback::new(secret, &guess);
+            Turn::new(guess, feedback)
+        }
+
+        #[test]
+        fn test_no_history_admits_everything() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let deductions = Deductions::new(&rules, &[]);
+
+            assert!(!deductions.is_contradictory());
+            assert!(deductions.facts().is_empty());
+            assert_eq!(deductions.admitted_codes().len(), 64);
+        }
+
+        #[test]
+        fn test_absent_symbols() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let secret: Code = vec![0, 1, 2, 3];
+            let deductions = Deductions::new(&rules, &[turn(&secret, vec![4, 4, 5, 5])]);
+
+            assert!(deductions.is_absent(4));
+            assert!(deductions.is_absent(5));
+            assert!(deductions.facts().contains(&Fact::Absent { symbol: 4 }));
+            assert_eq!(deductions.possible_symbols(2), vec![0, 1, 2, 3]);
+        }
+
+        #[test]
+        fn test_position_and_count_facts() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            let secret: Code = vec![1, 1, 0];
+            let history = vec![turn(&secret, vec![2, 2, 2]), turn(&secret, vec![1, 1, 1])];
+            let deductions = Deductions::new(&rules, &history);
+
+            assert!(deductions.is_absent(2));
+            assert_eq!(deductions.count_bounds(1), (2, 2));
+            assert_eq!(deductions.count_bounds(0), (1, 1));
+            assert_eq!(deductions.admitted_codes().len(), 3);
+        }
+
+        #[test]
+        fn test_deductions_are_sound() {
+            let rules = Rules::new(4, Limit::NoLimitation, 4).unwrap();
+            let secret: Code = vec![2, 0, 0, 3];
+            let history = vec![
+                turn(&secret, vec![0, 0, 1, 1]),
+                turn(&secret, vec![1, 2, 3, 0]),
+                turn(&secret, vec![3, 3, 0, 2]),
+            ];
+            let deductions = Deductions::new(&rules, &history);
+
+            assert!(!deductions.is_contradictory());
+            for code in solver::all_codes(&rules) {
+                if solver::is_consistent(&rules, &code, &history) {
+                    assert!(deductions.admits(&code), "{:?} should be admitted", code);
+                }
+            }
+        }
+
+        #[test]
+        fn test_contradiction_detected() {
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            let history = vec![
+                Turn::new(vec![0, 0], Feedback::new(&vec![0, 1], &vec![0, 0])),
+                Turn::new(vec![0, 0], Feedback::new(&vec![1, 1], &vec![0, 0])),
+            ];
+            let deductions = Deductions::new(&rules, &history);
+
+            assert!(deductions.is_contradictory());
+            assert!(deductions.admitted_codes().is_empty());
+        }
+
+        #[test]
+        fn test_wrong_length_guess_is_contradictory() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let guess: Code = vec![0, 1, 2, 3];
+            let history = vec![Turn::new(guess.clone(), Feedback::new(&guess, &guess))];
+            let deductions = Deductions::new(&rules, &history);
+
+            assert!(deductions.is_contradictory());
+            assert!(solver::candidates(&rules, &history).is_empty());
+            assert_eq!(MinimaxSolver.next_guess(&rules, &history), None);
+        }
+    }
+
+    // Tests for Replay module
+    mod replay_tests {
+        use super::*;
+        use crate::game::Turn;
+        use crate::replay::{Replay, ReplayError};
+
+        #[test]
+        fn test_replay_round_trip() {
+            let rules = Rules::new(4, Limit::Attempts { count: 10 }, 6).unwrap();
+            let secret: Code = vec![1, 2, 3, 4];
+            let guess: Code = vec![0, 0, 1, 1];
+            let feedback = Feedback::new(&secret, &guess);
+            let replay = Replay::new(rules, vec![Turn::new(guess, feedback)], Some(secret));
+
+            let text = replay.to_string();
+            assert!(text.starts_with("rules 4 6 attempts 10\n"));
+            assert!(text.contains("turn 0 0 1 1 = 0 1\n"));
+            assert!(text.contains("secret 1 2 3 4\n"));
+
+            let parsed: Replay = text.parse().unwrap();
+            assert_eq!(parsed, replay);
+        }
+
+        #[test]
+        fn test_replay_parse_comments_and_limits() {
+            let text = "# a puzzle\nrules 3 5 unlimited\n\nturn 0 1 2 = 1 1\n";
+            let replay: Replay = text.parse().unwrap();
+
+            assert_eq!(replay.rules().code_len(), 3);
+            assert!(matches!(replay.rules().limit(), Limit::NoLimitation));
+            assert_eq!(replay.turns().len(), 1);
+            assert_eq!(replay.turns()[0].feedback().exact(), 1);
+            assert!(replay.secret().is_none());
+        }
+
+        #[test]
+        fn test_replay_parse_errors() {
+            assert!(matches!(
+                "turn 0 1 = 0 0".parse::<Replay>(),
+                Err(ReplayError::MissingRules)
+            ));
+            assert!(matches!(
+                "rules 0 6 unlimited".parse::<Replay>(),
+                Err(ReplayError::InvalidRules(RulesError::InvalidCodeLen))
+            ));
+            assert!(matches!(
+                "rules 2 6 unlimited\nturn 0 1 2 = 0 0".parse::<Replay>(),
+                Err(ReplayError::WrongCodeLength { line: 2 })
+            ));
+            assert!(matches!(
+                "rules 2 6 unlimited\nturn 0 1 0 0".parse::<Replay>(),
+                Err(ReplayError::Malformed { line: 2 })
+            ));
+            assert!(matches!(
+                "rules 2 6 unlimited\nturn 0 1 = 1 1".parse::<Replay>(),
+                Err(ReplayError::InvalidFeedback {
+                    line: 2,
+                    error: FeedbackError::Unreachable
+                })
+            ));
+        }
+    }
+
+    // Tests for Puzzle module
+    mod puzzle_tests {
+        use super::*;
+        use crate::game::Turn;
+        use crate::puzzle::{self, PuzzleError};
+        use crate::replay::Replay;
+        use rand::{SeedableRng, rngs::StdRng};
+
+        #[test]
+        fn test_solve_lists_consistent_codes() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            let secret: Code = vec![2, 0, 1];
+            let turns: Vec<Turn> = [vec![0, 0, 0], vec![1, 2, 2], vec![2, 1, 0]]
+                .into_iter()
+                .map(|guess| {
+                    let feedback = Feedback::new(&secret, &guess);
+                    Turn::new(guess, feedback)
+                })
+                .collect();
+
+            let solutions = puzzle::solve(&rules, &turns).unwrap();
+            assert!(solutions.contains(&secret));
+            assert_eq!(solutions, crate::solver::candidates(&rules, &turns));
+        }
+
+        #[test]
+        fn test_solve_reports_conflicting_turns() {
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            let turns = vec![
+                // No 0 and no 1 in the secret
+                Turn::new(vec![0, 1], Feedback::new(&vec![2, 2], &vec![0, 1])),
+                Turn::new(vec![1, 1], Feedback::new(&vec![2, 2], &vec![1, 1])),
+                // One 0 in the secret
+                Turn::new(vec![0, 0], Feedback::new(&vec![0, 1], &vec![0, 0])),
+            ];
+
+            assert_eq!(
+                puzzle::solve(&rules, &turns),
+                Err(PuzzleError::Contradiction {
+                    conflicting: vec![0, 2]
+                })
+            );
+        }
+
+        #[test]
+        fn test_generate_unique_minimal_puzzle() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let difficulty = puzzle::Difficulty {
+                max_rows: 6,
+                min_depth: 0,
+            };
+            let mut rng = StdRng::seed_from_u64(7);
+            let generated = puzzle::generate(&rules, &difficulty, 20, &mut rng).unwrap();
+
+            let turns = generated.turns();
+            assert!(turns.len() <= 6);
+            assert_eq!(
+                puzzle::solve(&rules, turns),
+                Ok(vec![generated.secret().clone()])
+            );
+            // No row can be dropped without losing uniqueness
+            for index in 0..turns.len() {
+                let mut without = turns.to_vec();
+                without.remove(index);
+                assert!(puzzle::solve(&rules, &without).unwrap().len() > 1);
+            }
+        }
+
+        #[test]
+        fn test_generated_puzzle_replay_has_no_secret() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            let difficulty = puzzle::Difficulty {
+                max_rows: 10,
+                min_depth: 0,
+            };
+            let mut rng = StdRng::seed_from_u64(1);
+            let generated = puzzle::generate(&rules, &difficulty, 20, &mut rng).unwrap();
+
+            let text = generated.replay(&rules).to_string();
+            let replay: Replay = text.parse().unwrap();
+            assert!(replay.secret().is_none());
+            assert_eq!(replay.turns(), generated.turns());
+        }
+
+        #[test]
+        fn test_generate_gives_up_on_impossible_difficulty() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let difficulty = puzzle::Difficulty {
+                max_rows: 0,
+                min_depth: 0,
+            };
+            let mut rng = StdRng::seed_from_u64(3);
+
+            assert!(puzzle::generate(&rules, &difficulty, 5, &mut rng).is_none());
+        }
+
+        #[test]
+        fn test_solve_rejects_wrong_code_length() {
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            let turns = vec![Turn::new(
+                vec![0, 1, 2],
+                Feedback::new(&vec![0, 1, 2], &vec![0, 1, 2]),
+            )];
+
+            assert_eq!(
+                puzzle::solve(&rules, &turns),
+                Err(PuzzleError::WrongCodeLength { turn: 0 })
+            );
+        }
+    }
+
+    // Tests for Reverse module
+    mod reverse_tests {
+        use super::*;
+        use crate::game::Turn;
+        use crate::reverse::{Progress, ReverseError, ReverseGame};
+        use crate::solver::{MinimaxSolver, Solver};
+
+        fn counts(exact: u8, misplaced: u8) -> Feedback {
+            Feedback::Pegs { exact, misplaced }
+        }
+
+        #[test]
+        fn test_reverse_game_breaks_secret() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let secret: Code = vec![5, 1, 1, 3];
+            let mut game = ReverseGame::new(rules, MinimaxSolver);
+
+            let mut progress = Progress::Playing;
+            for _ in 0..6 {
+                let guess = game.next_guess().unwrap().clone();
+                progress = game.respond(Feedback::new(&secret, &guess)).unwrap();
+                if progress != Progress::Playing {
+                    break;
+                }
+            }
+            assert_eq!(progress, Progress::Solved { secret });
+            assert!(game.history().len() <= 5);
+        }
+
+        #[test]
+        fn test_reverse_game_rejects_bad_input() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let mut game = ReverseGame::new(rules, MinimaxSolver);
+
+            assert_eq!(
+                game.respond(counts(0, 0)),
+                Err(ReverseError::NoPendingGuess)
+            );
+            game.next_guess();
+            assert_eq!(
+                game.respond(counts(3, 1)),
+                Err(ReverseError::InvalidFeedback(FeedbackError::Unreachable))
+            );
+            assert_eq!(game.correct(4, counts(0, 0)), Err(ReverseError::NoSuchTurn));
+        }
+
+        /// Plays a fixed list of guesses.
+        struct ScriptedSolver(Vec<Code>);
+
+        impl Solver for ScriptedSolver {
+            fn next_guess(&mut self, _rules: &Rules, history: &[Turn]) -> Option<Code> {
+                self.0.get(history.len()).cloned()
+            }
+        }
+
+        #[test]
+        fn test_reverse_game_points_to_mistyped_turn() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let guesses = vec![vec![0, 0, 1], vec![1, 2, 2], vec![2, 3, 0], vec![2, 2, 3]];
+            let mut game = ReverseGame::new(rules, ScriptedSolver(guesses));
+
+            // The secret is 2 2 3, but the first answer is mistyped as (0, 1)
+            game.next_guess();
+            assert_eq!(game.respond(counts(0, 1)), Ok(Progress::Playing));
+            game.next_guess();
+            assert_eq!(game.respond(counts(1, 1)), Ok(Progress::Playing));
+            game.next_guess();
+            let Ok(Progress::Inconsistent {
+                suspect: Some(suspect),
+            }) = game.respond(counts(1, 1))
+            else {
+                panic!("Expected an inconsistency");
+            };
+            assert_eq!(suspect.turn(), 0);
+            assert_eq!(
+                suspect.entered(),
+                &Feedback::from_counts(0, 1, game.rules()).unwrap()
+            );
+            assert_eq!(
+                suspect.likely(),
+                &Feedback::from_counts(0, 0, game.rules()).unwrap()
+            );
+
+            assert_eq!(game.correct(0, counts(0, 0)), Ok(Progress::Playing));
+            assert_eq!(game.next_guess(), Some(&vec![2, 2, 3]));
+            assert_eq!(
+                game.respond(counts(3, 0)),
+                Ok(Progress::Solved {
+                    secret: vec![2, 2, 3]
+                })
+            );
+        }
+    }
+
+    // Tests for the evil codemaker
+    mod adversarial_tests {
+        use super::*;
+        use crate::feedback::Mark;
+        use crate::scheme::Scheme;
+        use crate::solver::{self, MinimaxSolver, Solver};
+
+        #[test]
+        fn test_adversarial_keeps_largest_group() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            let mut game = Game::new_adversarial(rules);
+            assert!(game.is_adversarial());
+
+            // Against 0 0 0, a single 0 is the most common answer (12 of 27 codes)
+            let feedback = game.guess(vec![0, 0, 0]).unwrap();
+            assert_eq!(feedback, Feedback::from_counts(1, 0, game.rules()).unwrap());
+            assert!(game.resolved_secret().is_none());
+        }
+
+        #[test]
+        fn test_adversarial_game_resolves_secret() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let mut game = Game::new_adversarial(rules);
+
+            let mut won = false;
+            for _ in 0..10 {
+                let history = game.state().history();
+                let guess = MinimaxSolver.next_guess(game.rules(), history).unwrap();
+                let feedback = game.guess(guess).unwrap();
+                if feedback.exact() == 3 {
+                    won = true;
+                    break;
+                }
+            }
+            assert!(won);
+
+            // The settled secret explains every answer the codemaker gave
+            let secret = game.resolved_secret().unwrap().clone();
+            assert!(solver::is_consistent(
+                game.rules(),
+                &secret,
+                game.state().history()
+            ));
+        }
+
+        #[test]
+        fn test_adversarial_wordle_answers_are_fixed() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3)
+                .unwrap()
+                .with_scheme(Scheme::Wordle)
+                .unwrap();
+            let play = || {
+                let mut game = Game::new_adversarial(rules.clone());
+                [vec![0, 1, 2], vec![1, 1, 0]].map(|guess| game.guess(guess).unwrap())
+            };
+            // Groups of the same size and counts are common under Wordle
+            for _ in 0..50 {
+                assert_eq!(
+                    play(),
+                    [
+                        Feedback::Marks(vec![Mark::Present, Mark::Present, Mark::Absent]),
+                        Feedback::Marks(vec![Mark::Correct, Mark::Present, Mark::Present]),
+                    ]
+                );
+            }
+        }
+
+        #[test]
+        fn test_resolved_secret_of_fixed_game() {
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            let mut game = Game::with_secret(rules, vec![1, 2]).unwrap();
+            assert!(!game.is_adversarial());
+
+            game.guess(vec![2, 1]);
+            assert!(game.resolved_secret().is_none());
+            game.guess(vec![1, 2]);
+            assert_eq!(game.resolved_secret(), Some(&vec![1, 2]));
+        }
+    }
+
+    // Tests for Multi module
+    mod multi_tests {
+        use super::*;
+        use crate::game::GameStatus;
+        use crate::multi::{MultiGame, MultiGameError};
+
+        #[test]
+        fn test_multi_game_needs_a_board() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            assert!(matches!(
+                MultiGame::new(rules, 0),
+                Err(MultiGameError::NoBoards)
+            ));
+        }
+
+        #[test]
+        fn test_multi_game_scores_every_unsolved_board() {
+            let rules = Rules::new(2, Limit::Attempts { count: 5 }, 3).unwrap();
+            let mut game =
+                MultiGame::with_secrets(rules, vec![vec![0, 1], vec![2, 2], vec![1, 0]]).unwrap();
+            assert_eq!(game.boards(), 3);
+
+            let feedbacks = game.guess(vec![0, 1]).unwrap();
+            assert_eq!(feedbacks.len(), 3);
+            assert_eq!(feedbacks[0].as_ref().unwrap().exact(), 2);
+            assert_eq!(feedbacks[2].as_ref().unwrap().misplaced(), Some(2));
+            assert_eq!(game.solved_at(0), Some(0));
+
+            // The solved board no longer answers
+            let feedbacks = game.guess(vec![2, 2]).unwrap();
+            assert!(feedbacks[0].is_none());
+            assert_eq!(game.solved_at(1), Some(1));
+            assert_eq!(game.status(), GameStatus::InProgress);
+
+            game.guess(vec![1, 0]).unwrap();
+            assert_eq!(game.status(), GameStatus::Won);
+            assert!(game.guess(vec![1, 0]).is_none());
+
+            assert_eq!(game.history().len(), 3);
+            assert_eq!(game.board_history(0).len(), 1);
+            assert_eq!(game.board_history(2).len(), 3);
+        }
+
+        #[test]
+        fn test_multi_game_shared_attempt_limit() {
+            let rules = Rules::new(2, Limit::Attempts { count: 2 }, 3).unwrap();
+            let mut game = MultiGame::with_secrets(rules, vec![vec![0, 1], vec![2, 2]]).unwrap();
+
+            game.guess(vec![0, 1]).unwrap();
+            game.guess(vec![1, 1]).unwrap();
+            assert_eq!(game.status(), GameStatus::Lost);
+            assert!(game.guess(vec![2, 2]).is_none());
+            assert!(game.guess(vec![2]).is_none());
+        }
+
+        #[test]
+        fn test_game_status() {
+            let rules = Rules::new(2, Limit::Attempts { count: 2 }, 3).unwrap();
+            let mut game = Game::with_secret(rules, vec![1, 2]).unwrap();
+            assert_eq!(game.status(), GameStatus::InProgress);
+
+            game.guess(vec![0, 0]);
+            game.guess(vec![1, 2]);
+            assert_eq!(game.status(), GameStatus::Won);
+
+            let rules = Rules::new(2, Limit::Attempts { count: 1 }, 3).unwrap();
+            let mut game = Game::with_secret(rules, vec![1, 2]).unwrap();
+            game.guess(vec![0, 0]);
+            assert_eq!(game.status(), GameStatus::Lost);
+        }
+    }
+
+    // Tests for Static Mastermind
+    mod static_game_tests {
+        use super::*;
+        use crate::game::GameStatus;
+        use crate::solver;
+        use crate::static_game::{self, StaticError, StaticGame};
+        use std::collections::HashSet;
+
+        #[test]
+        fn test_static_game_flow() {
+            let rules = Rules::new(2, Limit::Attempts { count: 4 }, 3).unwrap();
+            let mut game = StaticGame::with_secret(rules, vec![2, 0]);
+
+            assert_eq!(game.final_guess(vec![2, 0]), Err(StaticError::NotSubmitted));
+            let turns = game.submit(vec![vec![0, 0], vec![1, 2]]).unwrap();
+            assert_eq!(turns.len(), 2);
+            assert_eq!(turns[0].feedback().exact(), 1);
+            assert_eq!(
+                game.submit(vec![vec![0, 0]]),
+                Err(StaticError::AlreadySubmitted)
+            );
+            assert_eq!(game.status(), GameStatus::InProgress);
+
+            assert_eq!(game.final_guess(vec![2, 0]), Ok(true));
+            assert_eq!(game.status(), GameStatus::Won);
+            assert_eq!(
+                game.final_guess(vec![2, 0]),
+                Err(StaticError::AlreadyFinished)
+            );
+        }
+
+        #[test]
+        fn test_static_game_rejects_bad_submissions() {
+            let rules = Rules::new(2, Limit::Attempts { count: 2 }, 3).unwrap();
+            let mut game = StaticGame::with_secret(rules, vec![2, 0]);
+
+            assert_eq!(
+                game.submit(vec![vec![0, 0], vec![1]]),
+                Err(StaticError::InvalidCode { index: 1 })
+            );
+            assert_eq!(
+                game.submit(vec![vec![0, 0], vec![1, 1]]),
+                Err(StaticError::TooManyGuesses)
+            );
+            assert!(game.submit(vec![vec![0, 1]]).is_ok());
+            assert_eq!(game.final_guess(vec![1, 1]), Ok(false));
+            assert_eq!(game.status(), GameStatus::Lost);
+        }
+
+        fn tells_apart(rules: &Rules, guesses: &[Code]) -> bool {
+            let codes = solver::all_codes(rules);
+            let signatures: HashSet<Vec<Feedback>> = codes
+                .iter()
+                .map(|code| guesses.iter().map(|g| Feedback::new(code, g)).collect())
+                .collect();
+            signatures.len() == codes.len()
+        }
+
+        #[test]
+        fn test_minimal_guess_set_tells_codes_apart() {
+            for (code_len, symbols) in [(1, 3), (2, 2), (2, 3), (3, 2), (3, 3)] {
+                let rules = Rules::new(code_len, Limit::NoLimitation, symbols).unwrap();
+                let guesses = static_game::minimal_guess_set(&rules);
+                assert!(tells_apart(&rules, &guesses));
+
+                // No smaller set works
+                let codes = solver::all_codes(&rules);
+                match guesses.len() {
+                    1 => assert!(!tells_apart(&rules, &[])),
+                    2 => assert!(
+                        codes
+                            .iter()
+                            .all(|a| !tells_apart(&rules, std::slice::from_ref(a)))
+                    ),
+                    3 => assert!(codes.iter().all(|a| {
+                        codes
+                            .iter()
+                            .all(|b| !tells_apart(&rules, &[a.clone(), b.clone()]))
+                    })),
+                    _ => panic!("Unexpected guess set size {}", guesses.len()),
+                }
+            }
+        }
+
+        #[test]
+        fn test_minimal_guess_set_sizes() {
+            let rules = Rules::new(1, Limit::NoLimitation, 4).unwrap();
+            assert_eq!(static_game::minimal_guess_set(&rules).len(), 3);
+
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            assert_eq!(static_game::minimal_guess_set(&rules).len(), 2);
+
+            let rules = Rules::new(3, Limit::NoLimitation, 1).unwrap();
+            assert!(static_game::minimal_guess_set(&rules).is_empty());
+        }
+    }
+
+    // Tests for feedback schemes
+    mod scheme_tests {
+        use super::*;
+        use crate::deduction::Deductions;
+        use crate::feedback::Mark;
+        use crate::game::{GameStatus, Turn};
+        use crate::replay::Replay;
+        use crate::scheme::Scheme;
+        use crate::solver::{self, ConsistentSolver, MinimaxSolver, Solver};
+
+        const SCHEMES: [Scheme; 4] = [
+            Scheme::Classic,
+            Scheme::ExactOnly,
+            Scheme::Wordle,
+            Scheme::BullsAndCows,
+        ];
+
+        #[test]
+        fn test_all_possible_matches_enumeration() {
+            for scheme in SCHEMES {
+                for (code_len, symbols) in [(2, 2), (3, 2), (2, 3), (3, 3), (3, 4), (4, 4)] {
+                    let Ok(rules) = Rules::new(code_len, Limit::NoLimitation, symbols)
+                        .unwrap()
+                        .with_scheme(scheme)
+                    else {
+                        continue;
+                    };
+                    let codes = solver::all_codes(&rules);
+                    let mut reached: Vec<Feedback> = Vec::new();
+                    for secret in &codes {
+                        for guess in &codes {
+                            let feedback = scheme.score(secret, guess);
+                            if !reached.contains(&feedback) {
+                                reached.push(feedback);
+                            }
+                        }
+                    }
+
+                    let all = Feedback::all_possible(&rules);
+                    let name = format!("{:?} {}x{}", scheme, code_len, symbols);
+                    assert_eq!(all.len(), reached.len(), "{}", name);
+                    assert!(reached.iter().all(|f| all.contains(f)), "{}", name);
+                }
+            }
+        }
+
+        #[test]
+        fn test_wordle_marks_repeated_symbols_once() {
+            let rules = Rules::new(4, Limit::NoLimitation, 3)
+                .unwrap()
+                .with_scheme(Scheme::Wordle)
+                .unwrap();
+            let feedback = rules.scheme().score(&vec![0, 1, 2, 2], &vec![2, 2, 2, 0]);
+
+            use Mark::*;
+            assert_eq!(
+                feedback,
+                Feedback::Marks(vec![Present, Absent, Correct, Present])
+            );
+            assert_eq!(feedback.exact(), 1);
+            assert_eq!(feedback.misplaced(), Some(2));
+            assert_eq!(
+                Feedback::from_marks(vec![Correct, Correct, Correct, Present], &rules),
+                Err(FeedbackError::Unreachable)
+            );
+            assert_eq!(
+                Feedback::from_counts(1, 2, &rules),
+                Err(FeedbackError::WrongScheme)
+            );
+        }
+
+        #[test]
+        fn test_exact_only_hides_misplaced() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6)
+                .unwrap()
+                .with_scheme(Scheme::ExactOnly)
+                .unwrap();
+            let feedback = rules.scheme().score(&vec![1, 2, 3, 4], &vec![1, 3, 2, 5]);
+
+            assert_eq!(feedback, Feedback::from_exact(1, &rules).unwrap());
+            assert_eq!(feedback.misplaced(), None);
+            assert_eq!(Feedback::all_possible(&rules).len(), 5);
+        }
+
+        #[test]
+        fn test_bulls_and_cows_forbids_repetition() {
+            let rules = Rules::new(4, Limit::NoLimitation, 3)
+                .unwrap()
+                .with_scheme(Scheme::BullsAndCows);
+            assert!(matches!(rules, Err(RulesError::NotEnoughSymbols)));
+
+            let rules = Rules::new(4, Limit::Attempts { count: 10 }, 10)
+                .unwrap()
+                .with_scheme(Scheme::BullsAndCows)
+                .unwrap();
+            assert_eq!(solver::all_codes(&rules).len(), 5040);
+            let mut game = Game::new(rules);
+            assert!(game.guess(vec![1, 2, 2, 3]).is_none());
+            assert!(game.guess(vec![1, 2, 3, 4]).is_some());
+        }
+
+        #[test]
+        fn test_deductions_admit_consistent_codes_under_every_scheme() {
+            for scheme in SCHEMES {
+                let rules = Rules::new(4, Limit::NoLimitation, 5)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                let secret: Code = vec![3, 0, 4, 1];
+                let history: Vec<Turn> = [vec![0, 1, 2, 3], vec![4, 0, 1, 2], vec![1, 3, 0, 4]]
+                    .into_iter()
+                    .map(|guess| Turn::new(guess.clone(), scheme.score(&secret, &guess)))
+                    .collect();
+                let deductions = Deductions::new(&rules, &history);
+
+                assert!(!deductions.is_contradictory(), "{:?}", scheme);
+                for code in solver::all_codes(&rules) {
+                    if solver::is_consistent(&rules, &code, &history) {
+                        assert!(deductions.admits(&code), "{:?} {:?}", scheme, code);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_solvers_win_under_every_scheme() {
+            for scheme in SCHEMES {
+                let rules = Rules::new(3, Limit::Attempts { count: 12 }, 4)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                for secret in [vec![0, 2, 3], vec![3, 1, 0]] {
+                    let solvers: [Box<dyn Solver>; 2] =
+                        [Box::new(ConsistentSolver), Box::new(MinimaxSolver)];
+                    for mut solver in solvers {
+                        let mut game = Game::with_secret(rules.clone(), secret.clone()).unwrap();
+                        while game.status() == GameStatus::InProgress {
+                            let guess = solver
+                                .next_guess(game.rules(), game.state().history())
+                                .unwrap();
+                            game.guess(guess).unwrap();
+                        }
+                        assert_eq!(game.status(), GameStatus::Won, "{:?}", scheme);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_replay_round_trip_keeps_scheme() {
+            let text = "rules 3 4 unlimited scheme wordle\nturn 0 1 2 = CAP\n";
+            let replay: Replay = text.parse().unwrap();
+            assert_eq!(replay.rules().scheme(), Scheme::Wordle);
+            assert_eq!(replay.to_string(), text);
+
+            let text = "rules 3 4 attempts 8 scheme exact\nturn 0 1 2 = 1\nsecret 0 3 3\n";
+            let replay: Replay = text.parse().unwrap();
+            assert_eq!(replay.to_string(), text);
+
+            let text = "rules 3 4 unlimited scheme bulls-and-cows\nturn 0 1 2 = 1 1\n";
+            assert_eq!(text.parse::<Replay>().unwrap().to_string(), text);
+        }
+    }
+
+    // Tests for packed codes
+    mod packed_tests {
+        use super::*;
+        use crate::packed::PackedCode;
+        use crate::solver;
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        #[test]
+        fn test_packed_code_round_trip() {
+            let code: Code = vec![15, 0, 7, 7, 3];
+            let packed = PackedCode::new(&code).unwrap();
+            assert_eq!(packed.unpack(), code);
+            assert_eq!(packed.len(), 5);
+
+            assert!(PackedCode::new(&[0; 17]).is_none());
+            assert!(PackedCode::new(&[16]).is_none());
+            assert!(PackedCode::new(&[15; 16]).is_some());
+        }
+
+        #[test]
+        fn test_packed_feedback_matches_classic() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let codes = solver::all_codes(&rules);
+            for secret in &codes {
+                let packed_secret = PackedCode::new(secret).unwrap();
+                for guess in &codes {
+                    let packed_guess = PackedCode::new(guess).unwrap();
+                    assert_eq!(
+                        packed_secret.feedback(&packed_guess),
+                        Feedback::new(secret, guess)
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_packed_feedback_on_largest_codes() {
+            let mut rng = StdRng::seed_from_u64(36);
+            for _ in 0..2000 {
+                // Few symbols make long runs of the same symbol likely
+                let symbols = rng.random_range(1..=16);
+                let secret: Code = (0..16).map(|_| rng.random_range(0..symbols)).collect();
+                let guess: Code = (0..16).map(|_| rng.random_range(0..symbols)).collect();
+                let packed = PackedCode::new(&secret).unwrap();
+                assert_eq!(
+                    packed.feedback(&PackedCode::new(&guess).unwrap()),
+                    Feedback::new(&secret, &guess)
+                );
+            }
+
+            let all_zero = PackedCode::new(&[0; 16]).unwrap();
+            assert_eq!(all_zero.peg_counts(&all_zero), (16, 16));
+        }
+    }
+
+    // Tests for the feedback lookup table
+    mod table_tests {
+        use super::*;
+        use crate::scheme::Scheme;
+        use crate::table::{FeedbackTable, TableError};
+
+        const MAX_BYTES: usize = 1 << 24;
+
+        #[test]
+        fn test_table_matches_scheme() {
+            for scheme in [Scheme::Classic, Scheme::Wordle, Scheme::BullsAndCows] {
+                let rules = Rules::new(3, Limit::NoLimitation, 4)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                let table = FeedbackTable::new(&rules, MAX_BYTES).unwrap();
+                for (g, guess) in table.codes().iter().enumerate() {
+                    for (s, secret) in table.codes().iter().enumerate() {
+                        assert_eq!(*table.feedback(s, g), scheme.score(secret, guess));
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_table_builds_rows_lazily() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let table = FeedbackTable::new(&rules, MAX_BYTES).unwrap();
+            assert_eq!(table.built_rows(), 0);
+
+            let secret = table.index_of(&vec![1, 2, 3, 4]).unwrap();
+            let guess = table.index_of(&vec![4, 3, 2, 1]).unwrap();
+            let id = table.feedback_id(secret, guess);
+            assert_eq!(
+                table.feedbacks()[id as usize],
+                Feedback::from_counts(0, 4, &rules).unwrap()
+            );
+            assert_eq!(table.id_of(&table.feedbacks()[id as usize]), Some(id));
+            assert_eq!(table.built_rows(), 1);
+            assert!(table.index_of(&vec![6, 0, 0, 0]).is_none());
+        }
+
+        #[test]
+        fn test_table_respects_memory_bound() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            assert!(matches!(
+                FeedbackTable::new(&rules, 1000),
+                Err(TableError::TooLarge { bytes: 1_679_616 })
+            ));
+        }
+
+        #[test]
+        fn test_table_save_and_load() {
+            let rules = Rules::new(3, Limit::Attempts { count: 8 }, 4).unwrap();
+            let table = FeedbackTable::new(&rules, MAX_BYTES).unwrap();
+            let mut saved = Vec::new();
+            table.save(&mut saved).unwrap();
+            assert!(saved.starts_with(b"mastermind-table 1 3 4 classic\n"));
+
+            let loaded = FeedbackTable::load(&saved[..], &rules, MAX_BYTES).unwrap();
+            assert_eq!(loaded.built_rows(), loaded.codes().len());
+            for guess in 0..table.codes().len() {
+                assert_eq!(loaded.row(guess), table.row(guess));
+            }
+
+            let other = Rules::new(3, Limit::NoLimitation, 5).unwrap();
+            assert!(matches!(
+                FeedbackTable::load(&saved[..], &other, MAX_BYTES),
+                Err(TableError::WrongRules)
+            ));
+            assert!(matches!(
+                FeedbackTable::load(&saved[..saved.len() - 1], &rules, MAX_BYTES),
+                Err(TableError::Malformed)
+            ));
+        }
+    }
+
+    // Tests for code enumeration
+    mod enumeration_tests {
+        use super::*;
+        use crate::enumeration::{CodeSpace, Codes};
+        use crate::scheme::Scheme;
+
+        fn odometer(rules: &Rules) -> Vec<Code> {
+            let n = rules.code_len() as u32;
+            let k = rules.available_symbols() as u32;
+            (0..k.pow(n))
+                .map(|i| {
+                    (0..n)
+                        .rev()
+                        .map(|p| (i / k.pow(p) % k) as u8)
+                        .collect::<Code>()
+                })
+                .filter(|code| rules.accepts(code))
+                .collect()
+        }
+
+        #[test]
+        fn test_codes_in_lexicographic_order() {
+            for scheme in [Scheme::Classic, Scheme::BullsAndCows] {
+                for (code_len, symbols) in [(1, 1), (3, 3), (3, 5), (4, 6)] {
+                    let rules = Rules::new(code_len, Limit::NoLimitation, symbols)
+                        .unwrap()
+                        .with_scheme(scheme)
+                        .unwrap();
+                    let codes: Vec<Code> = Codes::new(&rules).collect();
+                    assert_eq!(
+                        codes,
+                        odometer(&rules),
+                        "{:?} {}x{}",
+                        scheme,
+                        code_len,
+                        symbols
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_rank_and_unrank_follow_the_order() {
+            for scheme in [Scheme::Classic, Scheme::BullsAndCows] {
+                let rules = Rules::new(4, Limit::NoLimitation, 6)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                let space = CodeSpace::new(&rules).unwrap();
+                let mut count = 0;
+                for (rank, code) in space.iter().enumerate() {
+                    assert_eq!(space.rank(&code), Some(rank as u64));
+                    assert_eq!(space.unrank(rank as u64), Some(code));
+                    count += 1;
+                }
+                assert_eq!(space.size(), count);
+                assert_eq!(space.unrank(count), None);
+            }
+        }
+
+        #[test]
+        fn test_rank_rejects_codes_outside_the_rules() {
+            let rules = Rules::new(4, Limit::NoLimitation, 10)
+                .unwrap()
+                .with_scheme(Scheme::BullsAndCows)
+                .unwrap();
+            let space = CodeSpace::new(&rules).unwrap();
+
+            assert_eq!(space.size(), 5040);
+            assert_eq!(space.rank(&[9, 8, 7, 6]), Some(5039));
+            assert_eq!(space.rank(&[1, 2, 2, 3]), None);
+            assert_eq!(space.rank(&[1, 2, 3]), None);
+            assert_eq!(space.rank(&[1, 2, 3, 10]), None);
+        }
+
+        #[test]
+        fn test_large_spaces_stream() {
+            // 10^20 codes cannot be numbered by a u64, but can still be listed
+            let rules = Rules::new(20, Limit::NoLimitation, 10).unwrap();
+            assert!(CodeSpace::new(&rules).is_none());
+            let codes: Vec<Code> = Codes::new(&rules).take(3).collect();
+            assert_eq!(codes[2].last(), Some(&2));
+
+            let rules = Rules::new(16, Limit::NoLimitation, 15).unwrap();
+            let space = CodeSpace::new(&rules).unwrap();
+            assert_eq!(space.size(), 15u64.pow(16));
+            assert_eq!(space.rank(&[14; 16]), Some(space.size() - 1));
+            assert_eq!(space.unrank(space.size() - 1), Some(vec![14; 16]));
+        }
+    }
+
+    // Tests for symmetry reduction
+    mod symmetry_tests {
+        use super::*;
+        use crate::game::Turn;
+        use crate::scheme::Scheme;
+        use crate::solver::{self, MinimaxSolver, Solver};
+        use crate::symmetry::Symmetries;
+
+        fn permutations(items: Vec<u8>) -> Vec<Vec<u8>> {
+            if items.is_empty() {
+                return vec![Vec::new()];
+            }
+            (0..items.len())
+                .flat_map(|index| {
+                    let mut rest = items.clone();
+                    let first = rest.remove(index);
+                    permutations(rest).into_iter().map(move |mut tail| {
+                        tail.insert(0, first);
+                        tail
+                    })
+                })
+                .collect()
+        }
+
+        /// Every colour and position permutation that leaves each turn as it is,
+        /// applied to `code`.
+        fn orbit(rules: &Rules, history: &[Turn], code: &Code) -> Vec<Code> {
+            let n = rules.code_len();
+            let k = rules.available_symbols();
+            let scheme = rules.scheme();
+            let apply = |positions: &[u8], colors: &[u8], code: &Code| {
+                let mut mapped = vec![0; code.len()];
+                for (i, &symbol) in code.iter().enumerate() {
+                    mapped[positions[i] as usize] = colors[symbol as usize];
+                }
+                mapped
+            };
+            let mut orbit = Vec::new();
+            for positions in permutations((0..n).collect()) {
+                for colors in permutations((0..k).collect()) {
+                    // The history stays the same if each secret keeps its feedback
+                    let keeps_history = history.iter().all(|turn| {
+                        apply(&positions, &colors, turn.guess()) == *turn.guess()
+                            && solver::all_codes(rules).iter().all(|secret| {
+                                scheme.score(&apply(&positions, &colors, secret), turn.guess())
+                                    == *turn.feedback()
+                                    || scheme.score(secret, turn.guess()) != *turn.feedback()
+                            })
+                    });
+                    if keeps_history {
+                        orbit.push(apply(&positions, &colors, code));
+                    }
+                }
+            }
+            orbit
+        }
+
+        #[test]
+        fn test_canonical_is_smallest_of_orbit() {
+            for scheme in [Scheme::Classic, Scheme::Wordle] {
+                let rules = Rules::new(3, Limit::NoLimitation, 4)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                let secret: Code = vec![2, 0, 2];
+                for guesses in [
+                    vec![],
+                    vec![vec![0, 0, 1]],
+                    vec![vec![0, 1, 1], vec![1, 1, 2]],
+                ] {
+                    let history: Vec<Turn> = guesses
+                        .into_iter()
+                        .map(|guess: Code| Turn::new(guess.clone(), scheme.score(&secret, &guess)))
+                        .collect();
+                    let symmetries = Symmetries::new(&rules, &history);
+                    for code in solver::all_codes(&rules) {
+                        let smallest = orbit(&rules, &history, &code).into_iter().min().unwrap();
+                        assert_eq!(symmetries.canonical(&code), smallest, "{:?}", code);
+                        assert_eq!(symmetries.is_representative(&code), smallest == code);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_symmetries_of_first_guess() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let opening = Symmetries::new(&rules, &[]);
+            let representatives: Vec<Code> = solver::all_codes(&rules)
+                .into_iter()
+                .filter(|code| opening.is_representative(code))
+                .collect();
+            assert_eq!(representatives.len(), 5);
+            assert_eq!(representatives[1], vec![0, 0, 0, 1]);
+
+            let guess: Code = vec![0, 0, 1, 1];
+            let history = vec![Turn::new(
+                guess.clone(),
+                Feedback::new(&vec![1, 2, 3, 4], &guess),
+            )];
+            let symmetries = Symmetries::new(&rules, &history);
+            assert_eq!(symmetries.free_colors(), &[2, 3, 4, 5]);
+            // Swapping the first two positions, the last two, or both halves with the colours
+            assert_eq!(symmetries.position_permutations(), 8);
+            assert_eq!(symmetries.canonical(&vec![5, 1, 0, 4]), vec![1, 2, 0, 3]);
+        }
+
+        #[test]
+        fn test_minimax_opening_for_larger_rules() {
+            let rules = Rules::new(5, Limit::NoLimitation, 8).unwrap();
+            let guess = MinimaxSolver.next_guess(&rules, &[]).unwrap();
+            assert!(Symmetries::new(&rules, &[]).is_representative(&guess));
+        }
+    }
+
+    mod analysis_tests {
+        use super::*;
+        use crate::analysis::{self, AnalysisError, Objective};
+        use crate::scheme::Scheme;
+        use crate::solver::{self, ConsistentSolver};
+        use crate::strategy::{BookSolver, Strategy, StrategyError};
+
+        /// Guesses the strategy needs to find the secret.
+        fn play(strategy: &Strategy, rules: &Rules, secret: &Code) -> usize {
+            let mut player = BookSolver::new(strategy.clone(), ConsistentSolver);
+            solver_game(&mut player, rules, secret, 20).len()
+        }
+
+        #[test]
+        fn test_average_objective() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            let analysis = analysis::analyse(&rules, Objective::Average, None).unwrap();
+            // Checked against a memoized brute force over every guess
+            assert_eq!(analysis.total_guesses(), 73);
+            assert_eq!(analysis.histogram(), &[1, 7, 18, 1]);
+            for secret in solver::all_codes(&rules) {
+                assert!(play(analysis.strategy(), &rules, &secret) <= 4);
+            }
+        }
+
+        #[test]
+        fn test_worst_case_objective() {
+            let rules = Rules::new(2, Limit::NoLimitation, 5).unwrap();
+            let average = analysis::analyse(&rules, Objective::Average, None).unwrap();
+            let worst_case = analysis::analyse(&rules, Objective::WorstCase, None).unwrap();
+            assert_eq!(average.total_guesses(), 81);
+            assert_eq!(average.worst_case(), 5);
+            // Same total, but no secret needs a fifth guess
+            assert_eq!(worst_case.total_guesses(), 81);
+            assert_eq!(worst_case.worst_case(), 4);
+        }
+
+        #[test]
+        fn test_analysis_with_other_schemes() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3)
+                .unwrap()
+                .with_scheme(Scheme::Wordle)
+                .unwrap();
+            let analysis = analysis::analyse(&rules, Objective::Average, None).unwrap();
+            let secrets = solver::all_codes(&rules);
+            let total: usize = secrets
+                .iter()
+                .map(|secret| play(analysis.strategy(), &rules, secret))
+                .sum();
+            assert_eq!(total, analysis.total_guesses());
+            assert_eq!(analysis.strategy().secrets(), secrets.len());
+            // Wordle tells more than pegs, so it never does worse
+            assert!(analysis.total_guesses() <= 73);
+        }
+
+        #[test]
+        fn test_strategy_text_round_trip() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            let analysis = analysis::analyse(&rules, Objective::Average, None).unwrap();
+            let text = analysis.strategy().to_string();
+            assert_eq!(
+                Strategy::parse(&text, &rules).unwrap(),
+                *analysis.strategy()
+            );
+
+            assert_eq!(
+                Strategy::parse("0 0 1\n   0 0 -> 2 2 2 *\n", &rules),
+                Err(StrategyError::Malformed { line: 2 })
+            );
+            assert!(matches!(
+                Strategy::parse("0 0 1\n  3 1 -> 2 2 2 *\n", &rules),
+                Err(StrategyError::InvalidFeedback { line: 2, .. })
+            ));
+        }
+
+        #[test]
+        fn test_checkpoint_resumes() {
+            let path = std::env::temp_dir()
+                .join(format!("mastermind-analysis-{}.txt", std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            let fresh = analysis::analyse(&rules, Objective::Average, Some(&path)).unwrap();
+            let text = std::fs::read_to_string(&path).unwrap();
+            assert!(text.starts_with("rules 3 3 unlimited\nobjective average\n"));
+            assert!(text.contains(" exact "));
+
+            let resumed = analysis::analyse(&rules, Objective::Average, Some(&path)).unwrap();
+            assert_eq!(resumed.strategy(), fresh.strategy());
+
+            // An interruption in the middle of a record loses only that record
+            let cut = text
+                .lines()
+                .take(text.lines().count() / 2)
+                .collect::<Vec<_>>();
+            std::fs::write(&path, cut.join("\n") + "\n").unwrap();
+            let resumed = analysis::analyse(&rules, Objective::Average, Some(&path)).unwrap();
+            assert_eq!(resumed.total_guesses(), 73);
+
+            // Even in the middle of a line or of the header, and however many
+            // times the analysis resumes afterwards
+            for cut in [20, text.len() / 3, text.len() / 2, text.len() * 3 / 4] {
+                let text = std::fs::read_to_string(&path).unwrap();
+                let cut = if text.as_bytes()[cut - 1] == b'\n' {
+                    cut + 1
+                } else {
+                    cut
+                };
+                std::fs::write(&path, &text[..cut]).unwrap();
+                for _ in 0..3 {
+                    let resumed =
+                        analysis::analyse(&rules, Objective::Average, Some(&path)).unwrap();
+                    assert_eq!(resumed.total_guesses(), 73);
+                    let text = std::fs::read_to_string(&path).unwrap();
+                    assert!(text.starts_with("rules 3 3 unlimited\nobjective average\n"));
+                    assert!(text.ends_with('\n'));
+                }
+            }
+
+            let result = analysis::analyse(&rules, Objective::WorstCase, Some(&path));
+            assert!(matches!(result, Err(AnalysisError::CheckpointMismatch)));
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        /// Takes over a minute in a release build: `cargo test --release -- --ignored`
+        #[test]
+        #[ignore]
+        fn test_classic_published_results() {
+            // Koyama and Lai (1993): 5625 guesses in total, 4.340 on average
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let analysis = analysis::analyse(&rules, Objective::Average, None).unwrap();
+            assert_eq!(analysis.histogram(), &[1, 8, 91, 647, 547, 2]);
+            assert_eq!(analysis.total_guesses(), 5625);
+        }
+    }
+
+    mod strategy_tests {
+        use super::*;
+        use crate::game::Turn;
+        use crate::scheme::Scheme;
+        use crate::solver::{self, ConsistentSolver, MinimaxSolver, Solver};
+        use crate::strategy::{BookSolver, Strategy};
+
+        /// Plays the same code forever.
+        struct StubbornSolver;
+
+        impl Solver for StubbornSolver {
+            fn next_guess(&mut self, _rules: &Rules, _history: &[Turn]) -> Option<Code> {
+                Some(vec![0, 0, 0])
+            }
+        }
+
+        #[test]
+        fn test_tree_matches_solver() {
+            for scheme in [Scheme::Classic, Scheme::Wordle] {
+                let rules = Rules::new(3, Limit::NoLimitation, 4)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                let tree = Strategy::from_solver(&mut MinimaxSolver, &rules).unwrap();
+                let secrets = solver::all_codes(&rules);
+                assert_eq!(tree.secrets(), secrets.len());
+                let total: usize = secrets
+                    .iter()
+                    .map(|secret| solver_game(&mut MinimaxSolver, &rules, secret, 20).len())
+                    .sum();
+                assert_eq!(tree.total_guesses(), total);
+            }
+        }
+
+        #[test]
+        fn test_tree_of_failing_solver() {
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            assert_eq!(Strategy::from_solver(&mut StubbornSolver, &rules), None);
+        }
+
+        #[test]
+        fn test_exports() {
+            let rules = Rules::new(2, Limit::NoLimitation, 2).unwrap();
+            let tree = Strategy::from_solver(&mut ConsistentSolver, &rules).unwrap();
+            assert_eq!(
+                tree.to_string(),
+                "0 0 *\n  0 0 -> 1 1 *\n  1 0 -> 0 1 *\n    0 2 -> 1 0 *\n"
+            );
+            assert_eq!(Strategy::parse(&tree.to_string(), &rules).unwrap(), tree);
+            assert_eq!(
+                tree.to_json(),
+                concat!(
+                    r#"{"guess":[0,0],"solves":true,"branches":["#,
+                    r#"{"feedback":{"exact":0,"misplaced":0},"strategy":{"guess":[1,1],"solves":true,"branches":[]}},"#,
+                    r#"{"feedback":{"exact":1,"misplaced":0},"strategy":{"guess":[0,1],"solves":true,"branches":["#,
+                    r#"{"feedback":{"exact":0,"misplaced":2},"strategy":{"guess":[1,0],"solves":true,"branches":[]}}]}}]}"#,
+                )
+            );
+            let dot = tree.to_dot();
+            assert!(dot.starts_with("digraph strategy {\n"));
+            assert!(dot.contains("  n0 [label=\"0 0\", peripheries=2];\n"));
+            assert!(dot.contains("  n2 -> n3 [label=\"0 2\"];\n"));
+            assert_eq!(dot.matches(" -> ").count(), 3);
+        }
+
+        #[test]
+        fn test_book_solver() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let text = Strategy::from_solver(&mut MinimaxSolver, &rules)
+                .unwrap()
+                .to_string();
+            let book = Strategy::parse(&text, &rules).unwrap();
+            let mut player = BookSolver::new(book.clone(), ConsistentSolver);
+            for secret in solver::all_codes(&rules) {
+                assert_eq!(
+                    solver_game(&mut player, &rules, &secret, 20),
+                    solver_game(&mut MinimaxSolver, &rules, &secret, 20)
+                );
+            }
+
+            // Off the book, the fallback plays
+            let guess: Code = vec![3, 3, 3];
+            let history = vec![Turn::new(
+                guess.clone(),
+                Feedback::new(&vec![0, 1, 2], &guess),
+            )];
+            assert_eq!(book.follow(&history), None);
+            assert_eq!(
+                player.next_guess(&rules, &history),
+                ConsistentSolver.next_guess(&rules, &history)
+            );
+        }
+    }
+
+    mod book_tests {
+        use super::*;
+        use crate::book::{BookError, OpeningBook};
+        use crate::game::Turn;
+        use crate::scheme::Scheme;
+        use crate::solver::{MinimaxSolver, Solver};
+        use crate::strategy::{BookSolver, Strategy};
+
+        #[test]
+        fn test_book_matches_solver() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let mut book = OpeningBook::new();
+            assert!(book.generate(&mut MinimaxSolver, &rules));
+
+            let first = MinimaxSolver.next_guess(&rules, &[]).unwrap();
+            assert_eq!(book.lookup(&rules, &[]), Some(first.clone()));
+            for secret in [vec![1, 0, 3], vec![3, 3, 0], vec![2, 2, 2]] {
+                let history = vec![Turn::new(
+                    first.clone(),
+                    rules.scheme().score(&secret, &first),
+                )];
+                assert_eq!(
+                    book.lookup(&rules, &history),
+                    MinimaxSolver.next_guess(&rules, &history)
+                );
+            }
+
+            // Only the first two guesses are kept, and the limit does not matter
+            let opening = book.opening(&rules).unwrap();
+            assert!(
+                opening
+                    .branches()
+                    .iter()
+                    .all(|(_, reply)| reply.branches().is_empty())
+            );
+            let limited = Rules::new(3, Limit::Attempts { count: 5 }, 4).unwrap();
+            assert_eq!(book.lookup(&limited, &[]), Some(first));
+            let wordle = rules.clone().with_scheme(Scheme::Wordle).unwrap();
+            assert_eq!(book.lookup(&wordle, &[]), None);
+        }
+
+        #[test]
+        fn test_save_and_load() {
+            let mut book = OpeningBook::new();
+            for scheme in [Scheme::Classic, Scheme::Wordle] {
+                let rules = Rules::new(3, Limit::NoLimitation, 3)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                assert!(book.generate(&mut MinimaxSolver, &rules));
+            }
+            let mut saved = Vec::new();
+            book.save(&mut saved).unwrap();
+            let text = String::from_utf8(saved).unwrap();
+            assert!(text.starts_with("mastermind-book 1\nopening 3 3 classic\n"));
+            assert!(text.contains("\nend\nopening 3 3 wordle\n"));
+
+            let loaded = OpeningBook::load(text.as_bytes()).unwrap();
+            assert_eq!(loaded.len(), 2);
+            let rules = Rules::new(3, Limit::NoLimitation, 3).unwrap();
+            assert_eq!(loaded.opening(&rules), book.opening(&rules));
+
+            assert!(matches!(
+                OpeningBook::load("mastermind-book 2\n".as_bytes()),
+                Err(BookError::Malformed { line: 1 })
+            ));
+            assert!(matches!(
+                OpeningBook::load("mastermind-book 1\nopening 3 3 classic\n0 0 1 *\n".as_bytes()),
+                Err(BookError::Malformed { line: 2 })
+            ));
+            assert!(matches!(
+                OpeningBook::load(
+                    "mastermind-book 1\nopening 3 3 classic\n0 0 3\nend\n".as_bytes()
+                ),
+                Err(BookError::InvalidOpening { line: 2, .. })
+            ));
+        }
+
+        #[test]
+        fn test_solver_plays_given_book() {
+            let rules = Rules::new(3, Limit::NoLimitation, 7)
+                .unwrap()
+                .with_scheme(Scheme::ExactOnly)
+                .unwrap();
+            let mut book = OpeningBook::new();
+            book.insert(&rules, Strategy::new(vec![6, 6, 6], true, Vec::new()));
+            let opening = book.opening(&rules).unwrap().clone();
+            let mut player = BookSolver::new(opening, MinimaxSolver);
+
+            // Solvers only play from a book they are given
+            assert_ne!(MinimaxSolver.next_guess(&rules, &[]), Some(vec![6, 6, 6]));
+            assert_eq!(player.next_guess(&rules, &[]), Some(vec![6, 6, 6]));
+            let guess: Code = vec![6, 6, 6];
+            let history = vec![Turn::new(
+                guess.clone(),
+                rules.scheme().score(&vec![0, 1, 2], &guess),
+            )];
+            assert_eq!(book.lookup(&rules, &history), None);
+            assert_eq!(
+                player.next_guess(&rules, &history),
+                MinimaxSolver.next_guess(&rules, &history)
+            );
+        }
+
+        #[test]
+        fn test_open_reports_bad_files() {
+            let path =
+                std::env::temp_dir().join(format!("mastermind-book-{}.txt", std::process::id()));
+            let _ = std::fs::remove_file(&path);
+            assert!(matches!(OpeningBook::open(&path), Err(BookError::Io(_))));
+
+            std::fs::write(&path, "mastermind-book 1\nopening 3 3\n").unwrap();
+            assert!(matches!(
+                OpeningBook::open(&path),
+                Err(BookError::Malformed { line: 2 })
+            ));
+            let mut saved = Vec::new();
+            OpeningBook::new().save(&mut saved).unwrap();
+            std::fs::write(&path, saved).unwrap();
+            assert!(OpeningBook::open(&path).unwrap().is_empty());
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    mod genetic_tests {
+        use std::time::{Duration, Instant};
+
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        use super::*;
+        use crate::game::Turn;
+        use crate::genetic::{Budget, GeneticSolver};
+        use crate::scheme::Scheme;
+        use crate::solver::{self, Solver};
+
+        /// Plays a code picked at random among those consistent with the history.
+        struct RandomConsistentSolver(StdRng);
+
+        impl Solver for RandomConsistentSolver {
+            fn next_guess(&mut self, rules: &Rules, history: &[Turn]) -> Option<Code> {
+                let candidates = solver::candidates(rules, history);
+                let index = self.0.random_range(0..candidates.len());
+                candidates.into_iter().nth(index)
+            }
+        }
+
+        /// Guesses the solver needs to find the secret, giving up after 20.
+        fn play(solver: &mut impl Solver, rules: &Rules, secret: &Code) -> usize {
+            solver_game(solver, rules, secret, 20).len()
+        }
+
+        #[test]
+        fn test_genetic_beats_random_consistent() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let secrets: Vec<Code> = solver::all_codes(&rules).into_iter().step_by(32).collect();
+            let mut genetic = GeneticSolver::new(Budget::Evaluations(5000)).with_seed(7);
+            let mut random = RandomConsistentSolver(StdRng::seed_from_u64(3));
+            let genetic: usize = secrets.iter().map(|s| play(&mut genetic, &rules, s)).sum();
+            let random: usize = secrets.iter().map(|s| play(&mut random, &rules, s)).sum();
+            assert!(genetic < random, "{} >= {}", genetic, random);
+        }
+
+        #[test]
+        fn test_genetic_solves_large_rules() {
+            let rules = Rules::new(8, Limit::NoLimitation, 12).unwrap();
+            let secret: Code = vec![11, 3, 3, 0, 7, 9, 1, 3];
+            let mut genetic = GeneticSolver::new(Budget::Evaluations(20000)).with_seed(1);
+            assert!(play(&mut genetic, &rules, &secret) < 20);
+
+            let rules = Rules::new(4, Limit::NoLimitation, 10)
+                .unwrap()
+                .with_scheme(Scheme::BullsAndCows)
+                .unwrap();
+            let mut genetic = GeneticSolver::new(Budget::Evaluations(5000)).with_seed(1);
+            assert!(play(&mut genetic, &rules, &vec![9, 2, 0, 5]) < 20);
+        }
+
+        #[test]
+        fn test_genetic_budget() {
+            let rules = Rules::new(8, Limit::NoLimitation, 12).unwrap();
+            let secret: Code = vec![0, 1, 2, 3, 4, 5, 6, 7];
+            let history: Vec<Turn> = [vec![0, 0, 1, 1, 2, 2, 3, 3], vec![4, 4, 5, 5, 6, 6, 7, 7]]
+                .into_iter()
+                .map(|guess| Turn::new(guess.clone(), rules.scheme().score(&secret, &guess)))
+                .collect();
+
+            let start = Instant::now();
+            let mut timed = GeneticSolver::new(Budget::Time(Duration::from_millis(50)));
+            assert!(timed.next_guess(&rules, &history).is_some());
+            assert!(start.elapsed() < Duration::from_secs(5));
+
+            // The same seed plays the same guesses
+            let guesses: Vec<Code> = (0..2)
+                .map(|_| {
+                    GeneticSolver::new(Budget::Evaluations(2000))
+                        .with_seed(5)
+                        .next_guess(&rules, &history)
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(guesses[0], guesses[1]);
+        }
+    }
+
+    mod constraint_tests {
+        use std::time::{Duration, Instant};
+
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        use super::*;
+        use crate::constraint::{self, ConstraintSolver};
+        use crate::game::Turn;
+        use crate::scheme::Scheme;
+        use crate::solver::{self, Solver};
+
+        #[test]
+        fn test_finds_consistent_code_iff_one_exists() {
+            let mut rng = StdRng::seed_from_u64(11);
+            for scheme in [
+                Scheme::Classic,
+                Scheme::ExactOnly,
+                Scheme::Wordle,
+                Scheme::BullsAndCows,
+            ] {
+                let rules = Rules::new(4, Limit::NoLimitation, 5)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                let codes = solver::all_codes(&rules);
+                for _ in 0..40 {
+                    // Feedback from several secrets, so some histories have no solution
+                    let history: Vec<Turn> = (0..rng.random_range(1..5))
+                        .map(|_| {
+                            let secret = &codes[rng.random_range(0..codes.len())];
+                            let guess = codes[rng.random_range(0..codes.len())].clone();
+                            Turn::new(guess.clone(), scheme.score(secret, &guess))
+                        })
+                        .collect();
+                    let found = constraint::find_consistent(&rules, &history);
+                    match &found {
+                        Some(code) => assert!(solver::is_consistent(&rules, code, &history)),
+                        None => assert!(solver::candidates(&rules, &history).is_empty()),
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_solves_ten_positions_quickly() {
+            // Debug builds are many times slower
+            let bound = if cfg!(debug_assertions) {
+                Duration::from_secs(2)
+            } else {
+                Duration::from_millis(100)
+            };
+            let mut rng = StdRng::seed_from_u64(5);
+            for scheme in [
+                Scheme::Classic,
+                Scheme::ExactOnly,
+                Scheme::Wordle,
+                Scheme::BullsAndCows,
+            ] {
+                let rules = Rules::new(10, Limit::NoLimitation, 10)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                for _ in 0..3 {
+                    let mut secret: Code = (0..10).collect();
+                    if rules.allows_repetition() {
+                        secret.iter_mut().for_each(|s| *s = rng.random_range(0..10));
+                    } else {
+                        secret.sort_by_key(|_| rng.random::<u32>());
+                    }
+                    let mut history: Vec<Turn> = Vec::new();
+                    let mut slowest = Duration::ZERO;
+                    while history.last().is_none_or(|turn| *turn.guess() != secret) {
+                        let start = Instant::now();
+                        let guess = ConstraintSolver.next_guess(&rules, &history).unwrap();
+                        slowest = slowest.max(start.elapsed());
+                        assert!(solver::is_consistent(&rules, &guess, &history));
+                        let feedback = rules.scheme().score(&secret, &guess);
+                        history.push(Turn::new(guess, feedback));
+                        assert!(history.len() <= 30);
+                    }
+                    assert!(slowest < bound, "{:?}: {:?}", scheme, slowest);
+                }
+            }
+        }
+
+        #[test]
+        fn test_mismatched_history() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let history = vec![Turn::new(
+                vec![0, 1],
+                Feedback::new(&vec![0, 1], &vec![0, 1]),
+            )];
+            assert_eq!(constraint::find_consistent(&rules, &history), None);
+        }
+    }
+
+    mod candidates_tests {
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        use super::*;
+        use crate::candidates::CandidateSet;
+        use crate::game::Turn;
+        use crate::scheme::Scheme;
+        use crate::solver;
+
+        fn turn(rules: &Rules, secret: &Code, guess: Code) -> Turn {
+            Turn::new(guess.clone(), rules.scheme().score(secret, &guess))
+        }
+
+        #[test]
+        fn test_filter_matches_candidates() {
+            let mut rng = StdRng::seed_from_u64(5);
+            for scheme in [
+                Scheme::Classic,
+                Scheme::ExactOnly,
+                Scheme::Wordle,
+                Scheme::BullsAndCows,
+            ] {
+                let rules = Rules::new(4, Limit::NoLimitation, 6)
+                    .unwrap()
+                    .with_scheme(scheme)
+                    .unwrap();
+                let codes = solver::all_codes(&rules);
+                let secret = &codes[rng.random_range(0..codes.len())];
+                let mut history = Vec::new();
+                let mut set = CandidateSet::full(&rules).unwrap();
+                assert_eq!(set.len(), codes.len());
+                for _ in 0..3 {
+                    let guess = codes[rng.random_range(0..codes.len())].clone();
+                    history.push(turn(&rules, secret, guess));
+                    set.filter(history.last().unwrap());
+                    let expected = solver::candidates(&rules, &history);
+                    assert_eq!(set.len(), expected.len());
+                    assert_eq!(set.iter().collect::<Vec<_>>(), expected);
+                    assert!(set.contains(secret));
+                }
+                assert_eq!(CandidateSet::from_history(&rules, &history).unwrap(), set);
+            }
+        }
+
+        #[test]
+        fn test_insert_and_remove() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let mut set = CandidateSet::empty(&rules).unwrap();
+            assert!(set.is_empty());
+            assert!(set.insert(&[5, 5, 5, 5]));
+            assert!(set.insert(&[0, 1, 2, 3]));
+            assert!(!set.insert(&[0, 1, 2]));
+            assert!(!set.insert(&[0, 1, 2, 6]));
+            assert_eq!(set.len(), 2);
+            assert_eq!(
+                set.iter().collect::<Vec<_>>(),
+                vec![vec![0, 1, 2, 3], vec![5, 5, 5, 5]]
+            );
+            set.remove(&[5, 5, 5, 5]);
+            assert!(!set.contains(&[5, 5, 5, 5]));
+            assert!(set.contains(&[0, 1, 2, 3]));
+
+            let rules = Rules::new(4, Limit::NoLimitation, 10)
+                .unwrap()
+                .with_scheme(Scheme::BullsAndCows)
+                .unwrap();
+            assert_eq!(CandidateSet::full(&rules).unwrap().len(), 5040);
+            assert!(
+                CandidateSet::full(&Rules::new(16, Limit::NoLimitation, 16).unwrap()).is_none()
+            );
+        }
+
+        #[test]
+        fn test_union_and_intersection() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let secret: Code = vec![1, 3, 3, 4];
+            let first = turn(&rules, &secret, vec![0, 0, 1, 1]);
+            let second = turn(&rules, &secret, vec![2, 3, 4, 5]);
+            let all = CandidateSet::full(&rules).unwrap();
+            let a = all.filtered(&first);
+            let b = all.filtered(&second);
+            assert_eq!(all.len(), 1296);
+
+            let both = a.intersection(&b);
+            assert_eq!(
+                both,
+                CandidateSet::from_history(&rules, &[first, second]).unwrap()
+            );
+            let either = a.union(&b);
+            assert_eq!(either.len(), a.len() + b.len() - both.len());
+            assert!(
+                either
+                    .iter()
+                    .all(|code| a.contains(&code) || b.contains(&code))
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "different rules")]
+        fn test_union_of_other_rules() {
+            let small =
+                CandidateSet::full(&Rules::new(3, Limit::NoLimitation, 6).unwrap()).unwrap();
+            let large =
+                CandidateSet::full(&Rules::new(4, Limit::NoLimitation, 6).unwrap()).unwrap();
+            small.union(&large);
+        }
+    }
+
+    mod quality_tests {
+        use std::collections::HashMap;
+
+        use super::*;
+        use crate::feedback::Feedback;
+        use crate::solver;
+
+        #[test]
+        fn test_first_guess_partition() {
+            let rules = Rules::new(4, Limit::NoLimitation, 6).unwrap();
+            let game = Game::new(rules);
+            let quality = game.evaluate(&[0, 0, 1, 1]).unwrap();
+
+            assert_eq!(quality.remaining(), 1296);
+            assert_eq!(quality.worst_case(), 256);
+            assert!(quality.could_be_secret());
+            let sizes: Vec<usize> = quality.partition().iter().map(|(_, c)| c.len()).collect();
+            assert_eq!(sizes.iter().sum::<usize>(), 1296);
+            assert!(sizes.windows(2).all(|pair| pair[0] >= pair[1]));
+            let won = Feedback::Pegs {
+                exact: 4,
+                misplaced: 0,
+            };
+            assert_eq!(quality.after(&won).unwrap().len(), 1);
+            // Evaluating a guess does not play it
+            assert!(game.state().history().is_empty());
+        }
+
+        #[test]
+        fn test_measures_match_brute_force() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let mut game = Game::with_secret(rules.clone(), vec![1, 2, 3]).unwrap();
+            game.guess(vec![0, 1, 1]);
+            let guess = vec![1, 1, 2];
+            let quality = game.evaluate(&guess).unwrap();
+
+            let candidates = solver::candidates(&rules, game.state().history());
+            let mut classes: HashMap<Feedback, usize> = HashMap::new();
+            for code in &candidates {
+                *classes
+                    .entry(rules.scheme().score(code, &guess))
+                    .or_default() += 1;
+            }
+            let total = candidates.len() as f64;
+            let expected: f64 = classes.values().map(|&n| (n * n) as f64).sum::<f64>() / total;
+            let entropy: f64 = classes
+                .values()
+                .map(|&n| -(n as f64 / total) * (n as f64 / total).log2())
+                .sum();
+
+            assert_eq!(quality.remaining(), candidates.len());
+            assert_eq!(quality.partition().len(), classes.len());
+            assert_eq!(quality.worst_case(), *classes.values().max().unwrap());
+            assert!((quality.expected_size() - expected).abs() < 1e-9);
+            assert!((quality.entropy() - entropy).abs() < 1e-9);
+            assert_eq!(quality.could_be_secret(), candidates.contains(&guess));
+            for (feedback, codes) in quality.partition() {
+                assert!(
+                    codes
+                        .iter()
+                        .all(|code| rules.scheme().score(&code, &guess) == *feedback)
+                );
+            }
+        }
+
+        #[test]
+        fn test_inconsistent_and_invalid_guesses() {
+            let rules = Rules::new(2, Limit::NoLimitation, 3).unwrap();
+            let mut game = Game::with_secret(rules, vec![2, 1]).unwrap();
+            game.guess(vec![0, 0]);
+
+            let quality = game.evaluate(&[0, 1]).unwrap();
+            assert!(!quality.could_be_secret());
+            assert_eq!(quality.remaining(), 4);
+            assert_eq!(quality.entropy(), 1.5);
+            assert_eq!(quality.expected_size(), 1.5);
+
+            assert!(game.evaluate(&[0, 1, 2]).is_none());
+            assert!(game.evaluate(&[0, 3]).is_none());
+        }
+    }
+
+    mod report_tests {
+        use super::*;
+        use crate::replay::Replay;
+        use crate::report::GameReport;
+        use crate::solver::MinimaxSolver;
+
+        #[test]
+        fn test_solver_game_has_no_blunders() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let secret = vec![3, 1, 3];
+            let history = solver_game(&mut MinimaxSolver, &rules, &secret, 20);
+            let report = GameReport::new(&mut MinimaxSolver, &rules, &history).unwrap();
+
+            assert!(report.is_won());
+            assert_eq!(report.blunders().count(), 0);
+            assert_eq!(report.turns().len(), history.len());
+            assert_eq!(report.turns()[0].before(), 64);
+            for pair in report.turns().windows(2) {
+                assert!(pair[1].before() < pair[0].before());
+                assert!(pair[0].bits() > 0.0);
+            }
+            let last = report.turns().last().unwrap();
+            assert_eq!(last.left(), 0);
+            assert_eq!(last.best(), last.guess());
+        }
+
+        #[test]
+        fn test_blunders_from_replay() {
+            let replay: Replay = "rules 3 4 unlimited\n\
+                                  turn 0 0 0 = 0 0\n\
+                                  turn 0 0 0 = 0 0\n\
+                                  turn 1 2 3 = 1 2\n"
+                .parse()
+                .unwrap();
+            let report = GameReport::from_replay(&replay).unwrap();
+
+            assert!(!report.is_won());
+            let turns = report.turns();
+            assert_eq!((turns[0].before(), turns[0].left()), (64, 27));
+            assert!((turns[0].bits() - (64.0f64 / 27.0).log2()).abs() < 1e-9);
+            // Repeating a guess tells nothing new
+            assert_eq!(turns[1].before(), turns[1].left());
+            assert_eq!(turns[1].bits(), 0.0);
+            assert!(turns[1].is_blunder());
+            assert_eq!(turns[2].left(), 3);
+
+            let text = report.to_string();
+            assert!(text.starts_with("1. 0 0 0 = 0 0: 64 -> 27 codes"));
+            assert!(text.contains("blunder"));
+            let json = report.to_json();
+            assert!(json.starts_with(
+                "{\"turns\":[{\"guess\":[0,0,0],\"feedback\":{\"exact\":0,\"misplaced\":0}"
+            ));
+            assert!(json.ends_with(&format!(
+                "\"won\":false,\"blunders\":{}}}",
+                report.blunders().count()
+            )));
+        }
+
+        #[test]
+        fn test_contradictory_turns() {
+            let replay: Replay = "rules 2 3 unlimited\nturn 0 0 = 2 0\nturn 1 1 = 1 0\n"
+                .parse()
+                .unwrap();
+            assert!(GameReport::from_replay(&replay).is_none());
+        }
+    }
+
+    mod rating_tests {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        use super::*;
+        use crate::analysis::{self, Objective};
+        use crate::rating::{self, RulesRating};
+        use crate::solver::{self, MinimaxSolver};
+
+        #[test]
+        fn test_rules_rating() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let rating = RulesRating::new(&mut MinimaxSolver, &rules).unwrap();
+
+            assert_eq!(rating.space_size(), 64);
+            assert_eq!(rating.bits(), 6.0);
+            assert_eq!(rating.histogram().iter().sum::<usize>(), 64);
+            assert_eq!(rating.worst_case(), rating.histogram().len());
+            let total = rating.strategy().total_guesses() as f64;
+            assert_eq!(rating.expected_guesses(), total / 64.0);
+
+            let optimal = analysis::analyse(&rules, Objective::Average, None).unwrap();
+            let best = RulesRating::from_strategy(&rules, optimal.strategy().clone()).unwrap();
+            assert!(best.expected_guesses() <= rating.expected_guesses());
+
+            // More symbols make the rules harder
+            let harder = Rules::new(3, Limit::NoLimitation, 5).unwrap();
+            let harder = RulesRating::new(&mut MinimaxSolver, &harder).unwrap();
+            assert!(harder.expected_guesses() > rating.expected_guesses());
+        }
+
+        #[test]
+        fn test_secret_ratings_match_games() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let rating = RulesRating::new(&mut MinimaxSolver, &rules).unwrap();
+
+            for secret in solver::all_codes(&rules) {
+                let secret_rating = rating.secret(&secret).unwrap();
+                let played = rating::guesses_needed(&mut MinimaxSolver, &rules, &secret, 10);
+                assert_eq!(Some(secret_rating.guesses()), played);
+                assert!(
+                    rating
+                        .secrets_needing(secret_rating.guesses())
+                        .contains(&secret)
+                );
+            }
+            let first = rating.strategy().guess().clone();
+            assert_eq!(rating.secret(&first).unwrap().percentile(), 0.0);
+            assert_eq!(
+                rating::guesses_needed(&mut MinimaxSolver, &rules, &first, 0),
+                None
+            );
+            assert!(rating.secret(&vec![0, 0, 4]).is_none());
+        }
+
+        #[test]
+        fn test_pick_secret_of_difficulty() {
+            let rules = Rules::new(3, Limit::NoLimitation, 4).unwrap();
+            let rating = RulesRating::new(&mut MinimaxSolver, &rules).unwrap();
+            let mut rng = StdRng::seed_from_u64(3);
+
+            let hardest = rating.worst_case();
+            let secret = rating.pick_secret(hardest, &mut rng).unwrap();
+            let secret_rating = rating.secret(&secret).unwrap();
+            assert_eq!(secret_rating.guesses(), hardest);
+            assert!(secret_rating.percentile() > 0.5);
+            assert_eq!(rating.pick_secret(hardest + 1, &mut rng), None);
+            assert!(rating.secrets_needing(0).is_empty());
+        }
+    }
 }
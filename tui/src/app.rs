@@ -0,0 +1,330 @@
+//! State of the terminal frontend and how keys change it. Nothing here
+//! touches the terminal, so it can be driven from tests.
+
+use std::time::{Duration, Instant};
+
+use mastermind_core::{
+    game::{Code, Game, GameStatus},
+    rules::{Limit, Rules, RulesError},
+    scheme::Scheme,
+};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Symbols are typed as digits, so there are at most ten.
+pub const MAX_SYMBOLS: u8 = 10;
+pub const MAX_CODE_LEN: u8 = 8;
+
+/// Limits offered by the rules picker.
+pub const LIMITS: [Limit; 6] = [
+    Limit::Attempts { count: 10 },
+    Limit::Attempts { count: 12 },
+    Limit::Attempts { count: 8 },
+    Limit::Time { seconds: 120 },
+    Limit::Time { seconds: 300 },
+    Limit::NoLimitation,
+];
+
+pub const SCHEMES: [Scheme; 4] = [
+    Scheme::Classic,
+    Scheme::ExactOnly,
+    Scheme::Wordle,
+    Scheme::BullsAndCows,
+];
+
+/// A setting of the rules picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    CodeLen,
+    Symbols,
+    Limit,
+    Scheme,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [Field::CodeLen, Field::Symbols, Field::Limit, Field::Scheme];
+}
+
+/// The rules chosen before a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulesPicker {
+    selected: usize,
+    code_len: u8,
+    symbols: u8,
+    limit: usize,
+    scheme: usize,
+    error: Option<&'static str>,
+}
+
+impl Default for RulesPicker {
+    fn default() -> Self {
+        RulesPicker {
+            selected: 0,
+            code_len: 4,
+            symbols: 6,
+            limit: 0,
+            scheme: 0,
+            error: None,
+        }
+    }
+}
+
+impl RulesPicker {
+    pub fn selected(&self) -> Field {
+        Field::ALL[self.selected]
+    }
+
+    pub fn code_len(&self) -> u8 {
+        self.code_len
+    }
+
+    pub fn symbols(&self) -> u8 {
+        self.symbols
+    }
+
+    pub fn limit(&self) -> &Limit {
+        &LIMITS[self.limit]
+    }
+
+    pub fn scheme(&self) -> Scheme {
+        SCHEMES[self.scheme]
+    }
+
+    /// Why the last rules could not be used, if they could not.
+    pub fn error(&self) -> Option<&'static str> {
+        self.error
+    }
+
+    pub fn rules(&self) -> Result<Rules, RulesError> {
+        Rules::new(self.code_len, self.limit().clone(), self.symbols)?.with_scheme(self.scheme())
+    }
+
+    /// Changes the selected setting by one step, wrapping around.
+    fn change(&mut self, forward: bool) {
+        let step = |value: usize, count: usize| {
+            if forward {
+                (value + 1) % count
+            } else {
+                (value + count - 1) % count
+            }
+        };
+        match self.selected() {
+            Field::CodeLen => {
+                self.code_len = step(self.code_len as usize - 1, MAX_CODE_LEN as usize) as u8 + 1;
+            }
+            Field::Symbols => {
+                self.symbols = step(self.symbols as usize - 2, MAX_SYMBOLS as usize - 1) as u8 + 2;
+            }
+            Field::Limit => self.limit = step(self.limit, LIMITS.len()),
+            Field::Scheme => self.scheme = step(self.scheme, SCHEMES.len()),
+        }
+        self.error = None;
+    }
+}
+
+/// A game being played, with the guess being typed.
+pub struct Play {
+    game: Game,
+    input: Code,
+    started: Instant,
+    elapsed: Duration,
+    /// Why the last key was refused, if it was.
+    notice: Option<&'static str>,
+}
+
+impl Play {
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// The symbols typed so far for the next guess.
+    pub fn input(&self) -> &[u8] {
+        &self.input
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Time left under a time limit.
+    pub fn time_left(&self) -> Option<Duration> {
+        match self.game.rules().limit() {
+            Limit::Time { seconds } => {
+                Some(Duration::from_secs(*seconds as u64).saturating_sub(self.elapsed))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn notice(&self) -> Option<&'static str> {
+        self.notice
+    }
+
+    fn type_symbol(&mut self, symbol: u8) {
+        let rules = self.game.rules();
+        self.notice = if symbol >= rules.available_symbols() {
+            Some("no such symbol")
+        } else if self.input.len() == rules.code_len() as usize {
+            Some("the guess is complete")
+        } else if !rules.allows_repetition() && self.input.contains(&symbol) {
+            Some("symbols may not repeat")
+        } else {
+            self.input.push(symbol);
+            None
+        };
+    }
+
+    fn submit(&mut self) {
+        if self.input.len() < self.game.rules().code_len() as usize {
+            self.notice = Some("the guess is incomplete");
+            return;
+        }
+        let guess = std::mem::take(&mut self.input);
+        self.notice = self.game.guess(guess).is_none().then_some("invalid guess");
+    }
+}
+
+/// How a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won,
+    OutOfAttempts,
+    OutOfTime,
+}
+
+pub enum Screen {
+    Picker(RulesPicker),
+    Playing(Play),
+    Over(Play, Outcome),
+}
+
+pub struct App {
+    screen: Screen,
+    /// The rules of the last game, offered again after it.
+    picker: RulesPicker,
+    quit: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    /// Starts on the rules picker.
+    pub fn new() -> Self {
+        App {
+            screen: Screen::Picker(RulesPicker::default()),
+            picker: RulesPicker::default(),
+            quit: false,
+        }
+    }
+
+    pub fn screen(&self) -> &Screen {
+        &self.screen
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Starts playing `game` at `now`.
+    pub fn play(&mut self, game: Game, now: Instant) {
+        self.screen = Screen::Playing(Play {
+            game,
+            input: Vec::new(),
+            started: now,
+            elapsed: Duration::ZERO,
+            notice: None,
+        });
+    }
+
+    /// Updates the clock, ending the game if its time is up.
+    pub fn tick(&mut self, now: Instant) {
+        if let Screen::Playing(play) = &mut self.screen {
+            play.elapsed = now.saturating_duration_since(play.started);
+            if play.time_left() == Some(Duration::ZERO) {
+                self.finish(Outcome::OutOfTime);
+            }
+        }
+    }
+
+    /// Handles a key pressed at `now`. Keys pressed once the time is up only
+    /// end the game.
+    pub fn handle_key(&mut self, key: KeyEvent, now: Instant) {
+        let ctrl_c =
+            key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c');
+        if ctrl_c || key.code == KeyCode::Char('q') {
+            self.quit = true;
+            return;
+        }
+        if let Screen::Playing(_) = self.screen {
+            self.tick(now);
+            if !matches!(self.screen, Screen::Playing(_)) {
+                return;
+            }
+        }
+        match &mut self.screen {
+            Screen::Picker(picker) => match key.code {
+                KeyCode::Up => {
+                    picker.selected = (picker.selected + Field::ALL.len() - 1) % Field::ALL.len();
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    picker.selected = (picker.selected + 1) % Field::ALL.len();
+                }
+                KeyCode::Left => picker.change(false),
+                KeyCode::Right => picker.change(true),
+                KeyCode::Enter => match picker.rules() {
+                    Ok(rules) => {
+                        self.picker = picker.clone();
+                        self.play(Game::new(rules), now);
+                    }
+                    Err(error) => picker.error = Some(describe(error)),
+                },
+                KeyCode::Esc => self.quit = true,
+                _ => {}
+            },
+            Screen::Playing(play) => {
+                match key.code {
+                    KeyCode::Char(c) if c.is_ascii_digit() => play.type_symbol(c as u8 - b'0'),
+                    KeyCode::Backspace => {
+                        play.input.pop();
+                        play.notice = None;
+                    }
+                    KeyCode::Enter => play.submit(),
+                    KeyCode::Esc => {
+                        self.screen = Screen::Picker(self.picker.clone());
+                        return;
+                    }
+                    _ => {}
+                }
+                match play.game.status() {
+                    GameStatus::Won => self.finish(Outcome::Won),
+                    GameStatus::Lost => self.finish(Outcome::OutOfAttempts),
+                    GameStatus::InProgress => {}
+                }
+            }
+            Screen::Over(..) => {
+                if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+                    self.screen = Screen::Picker(self.picker.clone());
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, outcome: Outcome) {
+        let screen = std::mem::replace(&mut self.screen, Screen::Picker(self.picker.clone()));
+        if let Screen::Playing(play) = screen {
+            self.screen = Screen::Over(play, outcome);
+        }
+    }
+}
+
+fn describe(error: RulesError) -> &'static str {
+    match error {
+        RulesError::InvalidCodeLen => "the code needs at least one position",
+        RulesError::InvalidLimit => "the limit must be above zero",
+        RulesError::InvalidAvailableSymbols => "there must be at least one symbol",
+        RulesError::NotEnoughSymbols => "this scheme needs as many symbols as positions",
+    }
+}
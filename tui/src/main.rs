@@ -0,0 +1,45 @@
+//! Terminal frontend: pick the rules, then play against a random secret.
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyEventKind},
+};
+
+use crate::app::App;
+
+mod app;
+mod ui;
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests;
+
+/// How often the timer is redrawn when no key is pressed.
+const TICK: Duration = Duration::from_millis(250);
+
+fn main() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    let mut app = App::new();
+    while !app.should_quit() {
+        app.tick(Instant::now());
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+        if event::poll(TICK)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key, Instant::now());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use mastermind_core::game::Game;
+    use mastermind_core::rules::{Limit, Rules};
+    use ratatui::{
+        Terminal,
+        backend::TestBackend,
+        crossterm::event::{KeyCode, KeyEvent},
+    };
+
+    use crate::app::{App, Field, Outcome, Screen};
+    use crate::ui;
+
+    fn press(app: &mut App, code: KeyCode, now: Instant) {
+        app.handle_key(KeyEvent::from(code), now);
+    }
+
+    fn type_code(app: &mut App, code: &str, now: Instant) {
+        for c in code.chars() {
+            press(app, KeyCode::Char(c), now);
+        }
+        press(app, KeyCode::Enter, now);
+    }
+
+    /// The screen as drawn, one string per row.
+    fn render(app: &App) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(70, 20)).unwrap();
+        terminal.draw(|frame| ui::draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn shows(screen: &[String], text: &str) -> bool {
+        screen.iter().any(|row| row.contains(text))
+    }
+
+    // Tests for the rules picker
+    mod picker_tests {
+        use super::*;
+
+        #[test]
+        fn test_picker_changes_rules_and_starts() {
+            let now = Instant::now();
+            let mut app = App::new();
+            assert!(shows(&render(&app), "< 4 >"));
+
+            press(&mut app, KeyCode::Right, now);
+            press(&mut app, KeyCode::Down, now);
+            press(&mut app, KeyCode::Left, now);
+            let Screen::Picker(picker) = app.screen() else {
+                panic!("the picker is shown first");
+            };
+            assert_eq!(picker.selected(), Field::Symbols);
+            assert_eq!((picker.code_len(), picker.symbols()), (5, 5));
+            assert!(shows(&render(&app), "< 5 >"));
+
+            press(&mut app, KeyCode::Enter, now);
+            let Screen::Playing(play) = app.screen() else {
+                panic!("Enter starts a game");
+            };
+            assert_eq!(play.game().rules().code_len(), 5);
+            assert!(shows(&render(&app), "Attempt 1 of 10"));
+        }
+
+        #[test]
+        fn test_picker_refuses_invalid_rules() {
+            let now = Instant::now();
+            let mut app = App::new();
+            // Eight positions, then bulls and cows, which needs eight symbols
+            for _ in 0..4 {
+                press(&mut app, KeyCode::Right, now);
+            }
+            for _ in 0..3 {
+                press(&mut app, KeyCode::Down, now);
+            }
+            for _ in 0..3 {
+                press(&mut app, KeyCode::Right, now);
+            }
+            press(&mut app, KeyCode::Enter, now);
+
+            assert!(matches!(app.screen(), Screen::Picker(_)));
+            assert!(shows(&render(&app), "as many symbols as positions"));
+        }
+
+        #[test]
+        fn test_quit() {
+            let mut app = App::new();
+            press(&mut app, KeyCode::Char('q'), Instant::now());
+            assert!(app.should_quit());
+        }
+    }
+
+    // Tests for playing a game
+    mod play_tests {
+        use super::*;
+
+        #[test]
+        fn test_symbol_entry() {
+            let now = Instant::now();
+            let mut app = App::new();
+            let rules = Rules::new(4, Limit::Attempts { count: 10 }, 6).unwrap();
+            app.play(Game::with_secret(rules, vec![1, 2, 3, 4]).unwrap(), now);
+
+            for key in ['1', '7', '2', '3', '4', '5'] {
+                press(&mut app, KeyCode::Char(key), now);
+            }
+            press(&mut app, KeyCode::Backspace, now);
+            press(&mut app, KeyCode::Char('5'), now);
+            let Screen::Playing(play) = app.screen() else {
+                panic!("the game is still going");
+            };
+            assert_eq!(play.input(), [1, 2, 3, 5]);
+            assert_eq!(play.notice(), None);
+
+            press(&mut app, KeyCode::Char('0'), now);
+            assert!(shows(&render(&app), "the guess is complete"));
+            press(&mut app, KeyCode::Enter, now);
+            let Screen::Playing(play) = app.screen() else {
+                panic!("the game is still going");
+            };
+            assert_eq!(play.game().state().history().len(), 1);
+            assert!(play.input().is_empty());
+
+            let screen = render(&app);
+            assert!(shows(&screen, "1   1  2  3  5   ●●●·"));
+            assert!(shows(&screen, "Attempt 2 of 10"));
+        }
+
+        #[test]
+        fn test_win_screen() {
+            let now = Instant::now();
+            let mut app = App::new();
+            let rules = Rules::new(3, Limit::Attempts { count: 10 }, 4).unwrap();
+            app.play(Game::with_secret(rules, vec![0, 1, 2]).unwrap(), now);
+
+            type_code(&mut app, "210", now);
+            type_code(&mut app, "012", now);
+            assert!(matches!(app.screen(), Screen::Over(_, Outcome::Won)));
+            let screen = render(&app);
+            assert!(shows(&screen, "You won in 2 guess(es)!"));
+            assert!(shows(&screen, "●○○"));
+
+            press(&mut app, KeyCode::Enter, now);
+            assert!(matches!(app.screen(), Screen::Picker(_)));
+        }
+
+        #[test]
+        fn test_loss_screens() {
+            let now = Instant::now();
+            let mut app = App::new();
+            let rules = Rules::new(2, Limit::Attempts { count: 2 }, 3).unwrap();
+            app.play(Game::with_secret(rules, vec![2, 2]).unwrap(), now);
+            type_code(&mut app, "00", now);
+            type_code(&mut app, "11", now);
+            assert!(matches!(
+                app.screen(),
+                Screen::Over(_, Outcome::OutOfAttempts)
+            ));
+            let screen = render(&app);
+            assert!(shows(&screen, "Out of attempts"));
+            assert!(shows(&screen, "The secret was  2  2"));
+
+            let rules = Rules::new(2, Limit::Time { seconds: 60 }, 3).unwrap();
+            app.play(Game::with_secret(rules, vec![2, 2]).unwrap(), now);
+            app.tick(now + Duration::from_secs(15));
+            assert!(shows(&render(&app), "time left 0:45"));
+            app.tick(now + Duration::from_secs(60));
+            assert!(matches!(app.screen(), Screen::Over(_, Outcome::OutOfTime)));
+            assert!(shows(&render(&app), "Out of time"));
+        }
+
+        #[test]
+        fn test_keys_after_the_deadline() {
+            let now = Instant::now();
+            let mut app = App::new();
+            let rules = Rules::new(2, Limit::Time { seconds: 60 }, 3).unwrap();
+            app.play(Game::with_secret(rules, vec![2, 2]).unwrap(), now);
+            press(&mut app, KeyCode::Char('2'), now + Duration::from_secs(30));
+            press(&mut app, KeyCode::Char('2'), now + Duration::from_secs(59));
+
+            // No tick came in between, and the winning guess is too late
+            press(&mut app, KeyCode::Enter, now + Duration::from_secs(61));
+            let Screen::Over(play, Outcome::OutOfTime) = app.screen() else {
+                panic!("the time is up");
+            };
+            assert!(play.game().state().history().is_empty());
+            assert!(shows(&render(&app), "Out of time"));
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Drawing of the [`App`] state. Nothing here changes the state.
+
+use std::time::Duration;
+
+use mastermind_core::{
+    feedback::{Feedback, Mark},
+    rules::{Limit, Rules},
+    scheme::Scheme,
+};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::app::{App, Field, Outcome, Play, RulesPicker, Screen};
+
+/// Colour of each symbol, by symbol number.
+const PALETTE: [Color; 10] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightBlue,
+    Color::White,
+];
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    match app.screen() {
+        Screen::Picker(picker) => draw_picker(frame, picker),
+        Screen::Playing(play) => draw_play(frame, play, true),
+        Screen::Over(play, outcome) => {
+            draw_play(frame, play, false);
+            draw_over(frame, play, *outcome);
+        }
+    }
+}
+
+fn draw_picker(frame: &mut Frame, picker: &RulesPicker) {
+    let mut lines = vec![Line::from("")];
+    for field in Field::ALL {
+        let (name, value) = match field {
+            Field::CodeLen => ("Code length", picker.code_len().to_string()),
+            Field::Symbols => ("Symbols", picker.symbols().to_string()),
+            Field::Limit => ("Limit", limit_name(picker.limit())),
+            Field::Scheme => ("Feedback", scheme_name(picker.scheme()).to_string()),
+        };
+        let style = if field == picker.selected() {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {:<12}", name)),
+            Span::styled(format!("< {} >", value), style),
+        ]));
+    }
+    lines.push(Line::from(""));
+    if let Some(error) = picker.error() {
+        lines.push(Line::styled(
+            format!("  {}", error),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    lines.push(Line::styled(
+        "  Up/Down choose, Left/Right change, Enter start, q quit",
+        Style::default().fg(Color::DarkGray),
+    ));
+    let block = Block::bordered().title(" Mastermind: new game ");
+    frame.render_widget(Paragraph::new(lines).block(block), frame.area());
+}
+
+/// Draws the board, with the guess being typed while `playing`.
+fn draw_play(frame: &mut Frame, play: &Play, playing: bool) {
+    let rules = play.game().rules();
+    let [header, board, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(3),
+        Constraint::Length(4),
+    ])
+    .areas(frame.area());
+
+    let title = format!(
+        " Mastermind: {} positions, {} symbols, {} ",
+        rules.code_len(),
+        rules.available_symbols(),
+        scheme_name(rules.scheme())
+    );
+    let status = Paragraph::new(status_line(play)).block(Block::bordered().title(title));
+    frame.render_widget(status, header);
+
+    // The rows that fit, keeping the latest ones
+    let mut rows: Vec<Line> = play
+        .game()
+        .state()
+        .history()
+        .iter()
+        .enumerate()
+        .map(|(index, turn)| {
+            let mut spans = vec![Span::raw(format!("{:>3}  ", index + 1))];
+            spans.extend(code_spans(turn.guess()));
+            spans.push(Span::raw("  "));
+            spans.extend(feedback_spans(turn.feedback(), rules));
+            Line::from(spans)
+        })
+        .collect();
+    if playing {
+        let mut spans = vec![Span::raw(format!("{:>3}  ", rows.len() + 1))];
+        spans.extend(code_spans(play.input()));
+        for _ in play.input().len()..rules.code_len() as usize {
+            spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        }
+        rows.push(Line::from(spans));
+    }
+    let visible = board.height.saturating_sub(2) as usize;
+    let rows = rows.split_off(rows.len().saturating_sub(visible));
+    frame.render_widget(Paragraph::new(rows).block(Block::bordered()), board);
+
+    let mut palette = vec![Span::raw("Symbols: ")];
+    palette.extend(code_spans(
+        &(0..rules.available_symbols()).collect::<Vec<_>>(),
+    ));
+    let mut help = vec![Line::from(palette)];
+    help.push(match play.notice() {
+        Some(notice) => Line::styled(notice, Style::default().fg(Color::Red)),
+        None => Line::styled(
+            "Digits type, Backspace erases, Enter guesses, Esc rules, q quits",
+            Style::default().fg(Color::DarkGray),
+        ),
+    });
+    frame.render_widget(Paragraph::new(help).block(Block::bordered()), footer);
+}
+
+fn draw_over(frame: &mut Frame, play: &Play, outcome: Outcome) {
+    let guesses = play.game().state().history().len();
+    let title = match outcome {
+        Outcome::Won => format!("You won in {} guess(es)!", guesses),
+        Outcome::OutOfAttempts => "Out of attempts".to_string(),
+        Outcome::OutOfTime => "Out of time".to_string(),
+    };
+    let color = if outcome == Outcome::Won {
+        Color::Green
+    } else {
+        Color::Red
+    };
+    let mut secret = vec![Span::raw("The secret was ")];
+    secret.extend(code_spans(play.game().reveal_secret()));
+    let lines = vec![
+        Line::styled(
+            title,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Line::from(""),
+        Line::from(secret),
+        Line::from(""),
+        Line::styled(
+            "Enter new game, q quit",
+            Style::default().fg(Color::DarkGray),
+        ),
+    ];
+    let area = centered(frame.area(), 44, 7);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Game over ")),
+        area,
+    );
+}
+
+/// The limit, with the attempts or the time left.
+fn status_line(play: &Play) -> Line<'static> {
+    let attempt = play.game().state().history().len() + 1;
+    let text = match play.game().rules().limit() {
+        Limit::Attempts { count } => {
+            format!("Attempt {} of {}", attempt.min(*count as usize), count)
+        }
+        Limit::Time { .. } => format!(
+            "Attempt {}, time left {}",
+            attempt,
+            clock(play.time_left().unwrap_or_default())
+        ),
+        Limit::NoLimitation => format!("Attempt {}, time {}", attempt, clock(play.elapsed())),
+    };
+    Line::from(text)
+}
+
+/// Each symbol as a coloured peg labelled with its key.
+fn code_spans(code: &[u8]) -> Vec<Span<'static>> {
+    code.iter()
+        .map(|&symbol| {
+            let color = PALETTE[symbol as usize % PALETTE.len()];
+            Span::styled(
+                format!(" {} ", symbol),
+                Style::default().fg(Color::Black).bg(color),
+            )
+        })
+        .collect()
+}
+
+/// Red pegs for exact symbols and white ones for misplaced symbols, or one
+/// coloured dot per position for Wordle marks.
+fn feedback_spans(feedback: &Feedback, rules: &Rules) -> Vec<Span<'static>> {
+    let peg = |color: Color| Span::styled("●", Style::default().fg(color));
+    match feedback {
+        Feedback::Marks(marks) => marks
+            .iter()
+            .map(|mark| match mark {
+                Mark::Correct => peg(Color::Green),
+                Mark::Present => peg(Color::Yellow),
+                Mark::Absent => peg(Color::DarkGray),
+            })
+            .collect(),
+        _ => {
+            let exact = feedback.exact() as usize;
+            let misplaced = feedback.misplaced().unwrap_or(0) as usize;
+            let empty = (rules.code_len() as usize).saturating_sub(exact + misplaced);
+            let mut spans = vec![peg(Color::Red); exact];
+            spans.extend(vec![
+                Span::styled("○", Style::default().fg(Color::White));
+                misplaced
+            ]);
+            spans.extend(vec![
+                Span::styled("·", Style::default().fg(Color::DarkGray));
+                empty
+            ]);
+            spans
+        }
+    }
+}
+
+fn limit_name(limit: &Limit) -> String {
+    match limit {
+        Limit::Attempts { count } => format!("{} attempts", count),
+        Limit::Time { seconds } => format!("{} time", clock(Duration::from_secs(*seconds as u64))),
+        Limit::NoLimitation => "none".to_string(),
+    }
+}
+
+fn scheme_name(scheme: Scheme) -> &'static str {
+    match scheme {
+        Scheme::Classic => "classic",
+        Scheme::ExactOnly => "exact only",
+        Scheme::Wordle => "wordle",
+        Scheme::BullsAndCows => "bulls and cows",
+    }
+}
+
+fn clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}